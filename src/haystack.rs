@@ -0,0 +1,37 @@
+//! The text types that owning splitters and regexes work on.
+
+use std::ops::{Index, Range};
+
+/// Text that an owning splitter can own, or that a regex searches: `str`
+/// or `[u8]`.
+pub trait Haystack: Index<Range<usize>, Output = Self> + 'static {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Where to search next after an empty match at `at`, which is before
+    /// the end: just past the `char` there, or the byte there.
+    fn next_start(&self, at: usize) -> usize;
+}
+
+impl Haystack for str {
+    fn len(&self) -> usize {
+        str::len(self)
+    }
+
+    fn next_start(&self, at: usize) -> usize {
+        at + self[at ..].chars().next().map_or(1, char::len_utf8)
+    }
+}
+
+impl Haystack for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn next_start(&self, at: usize) -> usize {
+        at + 1
+    }
+}
@@ -1,17 +1,312 @@
 use std::fmt;
 use std::ops::Deref;
+#[cfg(feature = "split_pattern")]
+use std::ops::Range;
+#[cfg(feature = "split_pattern")]
+use std::str::FromStr;
+#[cfg(feature = "split_pattern")]
+use std::sync::Arc;
 
-use rental::rental;
-use stable_deref_trait::StableDeref;
+use stable_deref_trait::{CloneStableDeref, StableDeref};
 
+#[cfg(feature = "regex")]
+use super::lex::{Lexer, Token, Tokens};
+#[cfg(feature = "regex")]
 use super::re;
+#[cfg(feature = "split_pattern")]
+use super::split_pattern::{self, IntoRegex, SplitPattern};
+use super::pattern::StrPattern;
+pub use super::self_ref::{BorrowIter, Checkpoint};
+use super::self_ref::SelfRef;
+
+// Defines the plain, `_map` and `_and_then` methods for one owning
+// splitter, given its `BorrowIter` and the type of its borrowed items.
+macro_rules! into_split_methods {
+    ($(
+        $plain:ident, $map:ident, $and_then:ident
+            [$($gen:tt)*] ($($arg:ident: $arg_ty:ty),*)
+            -> $Plain:ident, $Map:ident, $AndThen:ident [$($par:ident),*]
+            = $splitter:expr, $item:ty;
+    )*) => {$(
+        fn $plain<$($gen)*>(self $(, $arg: $arg_ty)*) -> $Plain<Self $(, $par)*> {
+            $Plain {
+                inner: SelfRef::new(self, $splitter),
+            }
+        }
+
+        fn $map<$($gen)* F, B>(self $(, $arg: $arg_ty)*, fun: F)
+                               -> $Map<Self $(, $par)*, F>
+        where
+            F: FnMut($item) -> B {
+
+            $Map {
+                inner: self.$plain($($arg),*).inner,
+                fun,
+            }
+        }
+
+        fn $and_then<$($gen)* F, I>(self $(, $arg: $arg_ty)*, fun: F)
+                                    -> $AndThen<Self $(, $par)*, F, I>
+        where
+            F: FnMut($item) -> I,
+            I: IntoIterator, {
+
+            $AndThen {
+                inner: self.$plain($($arg),*).inner,
+                fun,
+                rest:  None,
+            }
+        }
+    )*};
+}
+
+// Defines `remainder`, `offset` and `into_inner` in an owning splitter's
+// inherent impl. The text is a `str` unless given.
+macro_rules! owner_methods {
+    (in $Text:ty: $ctor:ident [$($gen:tt)*] ($($arg:ident: $arg_ty:ty),*)
+     $(where [$($bound:tt)*])? $(, clear $rest:ident)?) => {
+        /// Returns the part of the string not yet split off, that is,
+        /// everything between the pieces returned so far from either end.
+        /// Like `str::Split::remainder`, it leaves out the delimiters that
+        /// came after those pieces, so it is the rest of the text as-is.
+        pub fn remainder(&self) -> &$Text {
+            self.inner.remainder()
+        }
+
+        /// Returns where `remainder` begins in the string, in bytes, like
+        /// `RegexSplit::position`. For the `rsplit` families, which work
+        /// from the back, it is how far before the end the remainder ends.
+        pub fn offset(&self) -> usize {
+            self.inner.offset()
+        }
+
+        /// Gives back the string, discarding the splitter.
+        pub fn into_inner(self) -> S {
+            self.inner.into_inner()
+        }
+
+        /// Saves the current position, for `rewind` or `resume_from`.
+        pub fn checkpoint(&self) -> Checkpoint {
+            self.inner.checkpoint()
+        }
+
+        /// Carries on splitting from `checkpoint`, which should come from a
+        /// splitter with the same delimiter over the same text.
+        ///
+        /// This starts at the checkpoint’s offsets without going over the
+        /// text before them, except with `into_split_regex_keep`: a
+        /// delimiter there yields several items, which the offsets can’t
+        /// tell apart, so it splits the text again up to the checkpoint.
+        pub fn rewind(&mut self, checkpoint: Checkpoint) {
+            self.inner.rewind(checkpoint);
+            $(self.$rest = None;)?
+        }
+
+        /// Splits `owner` with the given arguments, starting from
+        /// `checkpoint` rather than from the beginning.
+        pub fn resume_from<$($gen)*>(owner: S $(, $arg: $arg_ty)*,
+                                     checkpoint: Checkpoint) -> Self
+        $(where $($bound)*)? {
+            let mut result = owner.$ctor($($arg),*);
+            result.rewind(checkpoint);
+            result
+        }
+    };
+
+    ($ctor:ident $($tail:tt)*) => {
+        owner_methods!(in str: $ctor $($tail)*);
+    };
+}
+
+// Defines the types returned by `into_split_methods!` for owners of type
+// `$Owner` (by default `IntoSplitIter`). Plain splitters convert each item
+// with `$own`. The plain and `_map` splitters are double-ended when the
+// borrowed iterator `$back` is.
+macro_rules! into_split_types {
+    (for $Owner:ident => $Text:ty; $(
+        $Plain:ident, $Map:ident, $AndThen:ident [$($par:ident: $bound:path),*]
+            = $splitter:ty, $item:ty => $owned:ty, $own:expr;
+            back if $back:ty;
+            new $plain:ident, $map:ident, $and_then:ident
+                ($($arg:ident: $arg_ty:ty),*);
+    )*) => {$(
+        #[derive(Clone, Debug)]
+        pub struct $Plain<S: $Owner $(, $par: $bound)*> {
+            inner: SelfRef<S, $splitter>,
+        }
+
+        impl<S: $Owner $(, $par: $bound)*> Iterator for $Plain<S $(, $par)*> {
+            type Item = $owned;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next_map($own)
+            }
+        }
+
+        impl<S, $($par),*> DoubleEndedIterator for $Plain<S $(, $par)*>
+        where
+            S: $Owner,
+            $($par: $bound,)*
+            for<'a> $back: DoubleEndedIterator, {
+
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back_map($own)
+            }
+        }
+
+        impl<S: $Owner $(, $par: $bound)*> $Plain<S $(, $par)*> {
+            owner_methods!(in $Text: $plain [] ($($arg: $arg_ty),*));
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct $Map<S: $Owner $(, $par: $bound)*, F> {
+            inner: SelfRef<S, $splitter>,
+            fun:   F,
+        }
+
+        impl<S, $($par,)* F, B> Iterator for $Map<S $(, $par)*, F>
+        where
+            S: $Owner,
+            $($par: $bound,)*
+            F: FnMut($item) -> B, {
+
+            type Item = B;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next_map(&mut self.fun)
+            }
+        }
+
+        impl<S, $($par,)* F, B> DoubleEndedIterator for $Map<S $(, $par)*, F>
+        where
+            S: $Owner,
+            $($par: $bound,)*
+            F: FnMut($item) -> B,
+            for<'a> $back: DoubleEndedIterator, {
+
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back_map(&mut self.fun)
+            }
+        }
+
+        impl<S: $Owner $(, $par: $bound)*, F> $Map<S $(, $par)*, F> {
+            owner_methods!(in $Text: $map [B] ($($arg: $arg_ty,)* fun: F)
+                           where [F: FnMut($item) -> B]);
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct $AndThen<S, $($par,)* F, I>
+        where
+            S: $Owner,
+            $($par: $bound,)*
+            I: IntoIterator, {
+
+            inner: SelfRef<S, $splitter>,
+            fun:   F,
+            rest:  Option<I::IntoIter>,
+        }
+
+        impl<S, $($par,)* F, I> Iterator for $AndThen<S $(, $par)*, F, I>
+        where
+            S: $Owner,
+            $($par: $bound,)*
+            F: FnMut($item) -> I,
+            I: IntoIterator, {
+
+            type Item = I::Item;
+
+            fn next(&mut self) -> Option<I::Item> {
+                loop {
+                    if let Some(result) = self.rest.as_mut().and_then(Iterator::next) {
+                        return Some(result);
+                    }
+
+                    self.rest = Some(self.inner.next_map(&mut self.fun)?.into_iter());
+                }
+            }
+        }
+
+        impl<S, $($par,)* F, I> $AndThen<S $(, $par)*, F, I>
+        where
+            S: $Owner,
+            $($par: $bound,)*
+            I: IntoIterator, {
+
+            owner_methods!(in $Text: $and_then [] ($($arg: $arg_ty,)* fun: F)
+                           where [F: FnMut($item) -> I], clear rest);
+        }
+    )*};
+
+    ($($tail:tt)*) => {
+        into_split_types!(for IntoSplitIter => str; $($tail)*);
+    };
+}
+
+// Adds `take_error` to the owning splitters over a regex `R`.
+#[cfg(feature = "split_pattern")]
+macro_rules! take_error {
+    ($(impl [$($gen:tt)*] $Type:ty $(where [$($bound:tt)*])?;)*) => {$(
+        impl<$($gen)*> $Type $(where $($bound)*)? {
+            /// Takes the error from the search that stopped the iterator
+            /// early, if one failed, as one with `fancy_regex` can. The
+            /// `remainder` is then the text not yet gone through.
+            pub fn take_error(&mut self)
+                              -> Option<<R::Regex as split_pattern::SplitPattern>::Error> {
+                self.inner.rent_mut(|split| split.take_error())
+            }
+        }
+    )*};
+}
+
+// Adds the lending methods to plain splitters whose items are `&str`.
+macro_rules! lend_str {
+    ($(impl [$($gen:tt)*] $Plain:ty;)*) => {$(
+        impl<$($gen)*> $Plain {
+            /// Returns the next piece without copying it into a `String`.
+            /// The piece borrows from the splitter, so it must be dropped
+            /// before the splitter advances again.
+            pub fn next_str(&mut self) -> Option<&str> {
+                self.inner.next_ref()
+            }
+
+            /// Calls `fun` on each remaining piece without allocating.
+            pub fn for_each_str<F>(mut self, mut fun: F)
+            where
+                F: FnMut(&str) {
+
+                while let Some(piece) = self.next_str() {
+                    fun(piece);
+                }
+            }
+
+            /// Folds the remaining pieces without allocating, stopping at
+            /// the first error.
+            pub fn try_fold_str<B, E, F>(&mut self, init: B, mut fun: F)
+                                         -> Result<B, E>
+            where
+                F: FnMut(B, &str) -> Result<B, E> {
+
+                let mut acc = init;
+                while let Some(piece) = self.next_str() {
+                    acc = fun(acc, piece)?;
+                }
+                Ok(acc)
+            }
+        }
+    )*};
+}
 
 /// Trait for owning-splitter methods. These methods work on `String`,
 /// `Box<str>`, `Rc<str>`, etc.
+///
+/// An owning splitter is `Send` or `Sync` whenever the string, the
+/// pattern or closure, and the regex it holds all are. So splitters over
+/// `String`, `Box<str>`, `Arc<str>` and `&str` can be sent to other
+/// threads, while those over `Rc<str>` cannot.
 pub trait IntoSplitIter: Deref<Target = str> + StableDeref + Sized {
     fn into_split_whitespace(self) -> IntoSplitWhitespace<Self> {
         IntoSplitWhitespace {
-            inner: inner::Whitespace::new(self, str::split_whitespace),
+            inner: SelfRef::new(self, inner::Whitespace),
         }
     }
 
@@ -43,433 +338,1602 @@ pub trait IntoSplitIter: Deref<Target = str> + StableDeref + Sized {
         }
     }
 
-    #[cfg(feature = "regex")]
-    fn into_split_regex(self, regex: re::Regex) -> IntoSplitRegex<Self> {
-        IntoSplitRegex {
-            inner: inner::Regex::new(
-                self,
-                move |_| Box::new(regex),
-                |r, s| re::Split(r.split(s)))
+    into_split_methods! {
+        into_split, into_split_map, into_split_and_then
+            [P: StrPattern,] (pat: P)
+            -> IntoSplit, IntoSplitMap, IntoSplitAndThen [P]
+            = inner::Split(pat), &str;
+
+        into_rsplit, into_rsplit_map, into_rsplit_and_then
+            [P: StrPattern,] (pat: P)
+            -> IntoRSplit, IntoRSplitMap, IntoRSplitAndThen [P]
+            = inner::RSplit(pat), &str;
+
+        into_split_terminator, into_split_terminator_map, into_split_terminator_and_then
+            [P: StrPattern,] (pat: P)
+            -> IntoSplitTerminator, IntoSplitTerminatorMap, IntoSplitTerminatorAndThen [P]
+            = inner::SplitTerminator(pat), &str;
+
+        into_splitn, into_splitn_map, into_splitn_and_then
+            [P: StrPattern,] (n: usize, pat: P)
+            -> IntoSplitN, IntoSplitNMap, IntoSplitNAndThen [P]
+            = inner::SplitN(n, pat), &str;
+
+        into_rsplitn, into_rsplitn_map, into_rsplitn_and_then
+            [P: StrPattern,] (n: usize, pat: P)
+            -> IntoRSplitN, IntoRSplitNMap, IntoRSplitNAndThen [P]
+            = inner::RSplitN(n, pat), &str;
+
+        into_split_inclusive, into_split_inclusive_map, into_split_inclusive_and_then
+            [P: StrPattern,] (pat: P)
+            -> IntoSplitInclusive, IntoSplitInclusiveMap, IntoSplitInclusiveAndThen [P]
+            = inner::SplitInclusive(pat), &str;
+
+        into_matches, into_matches_map, into_matches_and_then
+            [P: StrPattern,] (pat: P)
+            -> IntoMatches, IntoMatchesMap, IntoMatchesAndThen [P]
+            = inner::Matches(pat), &str;
+
+        into_match_indices, into_match_indices_map, into_match_indices_and_then
+            [P: StrPattern,] (pat: P)
+            -> IntoMatchIndices, IntoMatchIndicesMap, IntoMatchIndicesAndThen [P]
+            = inner::MatchIndices(pat), (usize, &str);
+
+        into_lines, into_lines_map, into_lines_and_then
+            [] ()
+            -> IntoLines, IntoLinesMap, IntoLinesAndThen []
+            = inner::Lines, &str;
+
+        into_split_ascii_whitespace, into_split_ascii_whitespace_map,
+        into_split_ascii_whitespace_and_then
+            [] ()
+            -> IntoSplitAsciiWhitespace, IntoSplitAsciiWhitespaceMap,
+               IntoSplitAsciiWhitespaceAndThen []
+            = inner::SplitAsciiWhitespace, &str;
+
+        into_char_indices, into_char_indices_map, into_char_indices_and_then
+            [] ()
+            -> IntoCharIndices, IntoCharIndicesMap, IntoCharIndicesAndThen []
+            = inner::CharIndices, (usize, char);
+    }
+
+    /// Packages `self` with the iterator that `iter` makes from it. Since
+    /// the items may borrow from `self`, each is passed to `fun`.
+    fn into_iter_with<P, F, R>(self, iter: P, fun: F) -> IntoIterWith<Self, P, F>
+    where
+        P: BorrowIter,
+        F: for<'a> FnMut(P::Item<'a>) -> R {
+
+        IntoIterWith {
+            inner: SelfRef::new(self, iter),
+            fun,
         }
     }
 
+    // Splits on matches of `regex`, which may come from any of the regex
+    // backends.
+    #[cfg(feature = "split_pattern")]
+    into_split_methods! {
+        into_split_regex, into_split_regex_map, into_split_regex_and_then
+            [R: IntoRegex,] (regex: R)
+            -> IntoSplitRegex, IntoSplitRegexMap, IntoSplitRegexAndThen [R]
+            = inner::Regex(regex.into_stable_regex()), &str;
+    }
+
+    /// Splits on matches of the regex `pattern`. Recently used patterns
+    /// are compiled only once, so this is cheap to call for each line
+    /// with a pattern that isn’t known until run time.
     #[cfg(feature = "regex")]
-    fn into_split_regex_map<F, R>(self, regex: re::Regex, fun: F)
-                                  -> IntoSplitRegexMap<Self, F>
+    fn into_split_pattern(self, pattern: &str)
+                          -> Result<IntoSplitRegex<Self, Arc<re::Regex>>, re::Error> {
+        Ok(self.into_split_regex(re::cached(pattern)?))
+    }
+
+    /// Like `into_split_pattern`, but compiles `pattern` with the backend
+    /// `R`, such as `regex_lite::Regex` or `fancy_regex::Regex`.
+    #[cfg(feature = "split_pattern")]
+    fn into_split_pattern_with<R>(self, pattern: &str)
+                                  -> Result<IntoSplitRegex<Self, Arc<R>>, R::Err>
     where
-        F: FnMut(&str) -> R {
+        R: SplitPattern<Text = str> + FromStr + Send + Sync + 'static,
+        Arc<R>: IntoRegex, {
 
-        IntoSplitRegexMap {
-            inner: self.into_split_regex(regex).inner,
-            fun
-        }
+        Ok(self.into_split_regex(split_pattern::cached(pattern)?))
+    }
+
+    // Like `into_split_regex`, but each piece is followed by the capture
+    // groups of the delimiter after it, or the whole delimiter if the
+    // regex has no groups.
+    #[cfg(feature = "split_pattern")]
+    into_split_methods! {
+        into_split_regex_keep, into_split_regex_keep_map, into_split_regex_keep_and_then
+            [R: IntoRegex,] (regex: R)
+            -> IntoSplitRegexKeep, IntoSplitRegexKeepMap, IntoSplitRegexKeepAndThen [R]
+            = inner::Keep(regex.into_stable_regex()), &str;
     }
 
+    // Lexes the string into owned tokens, while `_map` and `_and_then`
+    // see tokens that borrow their text.
     #[cfg(feature = "regex")]
-    fn into_split_regex_and_then<F, I>(self, regex: re::Regex, fun: F)
-                                       -> IntoSplitRegexAndThen<Self, F, I>
+    into_split_methods! {
+        into_tokens, into_tokens_map, into_tokens_and_then
+            [K: Clone,] (lexer: Lexer<K>)
+            -> IntoTokens, IntoTokensMap, IntoTokensAndThen [K]
+            = inner::Lex(lexer), Token<K, &str>;
+    }
+
+    // The plain form yields each match with its byte range, while `_map`
+    // and `_and_then` see the backend’s borrowed match, a `regex::Match`
+    // with the `regex` crate.
+    #[cfg(feature = "split_pattern")]
+    into_split_methods! {
+        into_find_iter, into_find_iter_map, into_find_iter_and_then
+            [R: IntoRegex,] (regex: R)
+            -> IntoFindIter, IntoFindIterMap, IntoFindIterAndThen [R]
+            = inner::Find(regex.into_stable_regex()),
+              <R::Regex as SplitPattern>::Match<'_>;
+    }
+
+    /// Yields the byte range of each match of `regex`, for when the text
+    /// isn’t needed.
+    #[cfg(feature = "split_pattern")]
+    fn into_match_spans<R>(self, regex: R) -> IntoMatchSpans<Self, R>
     where
-        F: FnMut(&str) -> I,
-        I: IntoIterator {
+        R: IntoRegex, {
 
-        IntoSplitRegexAndThen {
-            inner: self.into_split_regex(regex).inner,
-            fun,
-            rest:  None,
+        IntoMatchSpans {
+            inner: self.into_find_iter(regex).inner,
         }
     }
 
+    // The plain form yields `OwnedCaptures`, while `_map` and `_and_then`
+    // see the borrowed `regex::Captures`, so unlike the methods above it
+    // takes only a `regex::Regex`, not any `SplitPattern`.
     #[cfg(feature = "regex")]
-    fn into_split_regex_ref(self, regex: &re::Regex) -> IntoSplitRegexRef<Self> {
-        IntoSplitRegexRef {
-            inner: inner::RegexRef::new(
-                self,
-                move |s| re::Split(regex.split(s)))
-        }
+    into_split_methods! {
+        into_captures_iter, into_captures_iter_map, into_captures_iter_and_then
+            [R: IntoRegex<Regex = re::Regex>,] (regex: R)
+            -> IntoCapturesIter, IntoCapturesIterMap, IntoCapturesIterAndThen [R]
+            = inner::Captures::new(regex.into_stable_regex()), regex::Captures<'_>;
+    }
+}
+
+impl<T: Deref<Target = str> + StableDeref + Sized> IntoSplitIter for T { }
+
+/// Trait for owning-splitter methods on byte strings, which needn’t be
+/// UTF-8. These methods work on `Vec<u8>`, `Box<[u8]>`, `Rc<[u8]>`, etc.
+#[cfg(feature = "regex")]
+pub trait IntoSplitBytesIter: Deref<Target = [u8]> + StableDeref + Sized {
+    into_split_methods! {
+        into_split_regex, into_split_regex_map, into_split_regex_and_then
+            [R: IntoRegex<[u8]>,] (regex: R)
+            -> IntoSplitBytesRegex, IntoSplitBytesRegexMap,
+               IntoSplitBytesRegexAndThen [R]
+            = inner::Regex(regex.into_stable_regex()), &[u8];
     }
+}
+
+#[cfg(feature = "regex")]
+impl<T: Deref<Target = [u8]> + StableDeref + Sized> IntoSplitBytesIter for T { }
 
+mod inner {
+    use std::str;
+
+    use super::{BorrowIter, SelfRef, StrPattern};
+    use crate::self_ref::{Checkpoint, Piece, Pieces};
+
+    #[cfg(feature = "split_pattern")]
+    pub use self::any_regex::*;
     #[cfg(feature = "regex")]
-    fn into_split_regex_ref_map<F, R>(self, regex: &re::Regex, fun: F)
-                                      -> IntoSplitRegexRefMap<Self, F>
-    where
-        F: FnMut(&str) -> R {
+    pub use self::regex_only::*;
 
-        IntoSplitRegexRefMap {
-            inner: self.into_split_regex_ref(regex).inner,
-            fun,
+    #[derive(Clone, Debug)]
+    pub struct Whitespace;
+
+    impl BorrowIter for Whitespace {
+        type Item<'a> = &'a str;
+        type Iter<'a> = str::SplitWhitespace<'a>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            base.split_whitespace()
+        }
+    }
+
+    impl Pieces for Whitespace {
+        fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+            item.span(base)
+        }
+
+        fn trim(&self, base: &str, from: &Checkpoint) -> (usize, usize) {
+            trim_with(base, from, |rest| rest.trim_start(), |rest| rest.trim_end())
+        }
+    }
+
+    pub type WhitespaceIter<S> = SelfRef<S, Whitespace>;
+
+    // Trims the remainder with `front` if pieces were taken from the front,
+    // and with `back` if any were taken from the back.
+    fn trim_with<'a>(base: &'a str, from: &Checkpoint,
+                     front: impl Fn(&'a str) -> &'a str,
+                     back: impl Fn(&'a str) -> &'a str) -> (usize, usize) {
+        let mut rest = &base[from.front .. from.back];
+        if from.taken_front > 0 {
+            rest = front(rest);
+        }
+        if from.taken_back > 0 {
+            rest = back(rest);
+        }
+        rest.span(base)
+    }
+
+    fn trim_line_breaks(base: &str, from: &Checkpoint) -> (usize, usize) {
+        trim_with(base, from,
+                  |rest| rest.strip_prefix("\r\n")
+                             .or_else(|| rest.strip_prefix('\n'))
+                             .unwrap_or(rest),
+                  |rest| rest.strip_suffix("\r\n")
+                             .or_else(|| rest.strip_suffix('\n'))
+                             .unwrap_or(rest))
+    }
+
+    fn trim_ascii_whitespace(base: &str, from: &Checkpoint) -> (usize, usize) {
+        let is_space = |c: char| c.is_ascii_whitespace();
+        trim_with(base, from,
+                  |rest| rest.trim_start_matches(is_space),
+                  |rest| rest.trim_end_matches(is_space))
+    }
+
+    // Leaves out the delimiters next to the pieces taken. Once a piece has
+    // been taken from the front, the remainder starts with a delimiter, so
+    // its second piece begins just past it; likewise from the back.
+    fn trim_delimiters<P: StrPattern>(pat: &P, base: &str, from: &Checkpoint)
+                                      -> (usize, usize) {
+        let rest = &base[from.front .. from.back];
+        let mut front = from.front;
+        let mut back = from.back;
+        if from.taken_front > 0 {
+            if let Some(piece) = pat.clone().split_in(rest).nth(1) {
+                front = piece.span(base).0;
+            }
+        }
+        if from.taken_back > 0 {
+            if let Some(piece) = pat.clone().rsplit_in(rest).nth(1) {
+                back = piece.span(base).1;
+            }
+        }
+        (front, back)
+    }
+
+    // An iterator over `(index, item)` pairs from a slice of the text that
+    // begins `by` bytes in, with the indices counted from the start of the
+    // whole text, so that splitters whose items carry indices can resume
+    // from a checkpoint’s offsets.
+    #[derive(Clone, Debug)]
+    pub struct Shifted<I> {
+        iter: I,
+        by:   usize,
+    }
+
+    impl<I> Shifted<I> {
+        fn new(iter: I) -> Self {
+            Shifted { iter, by: 0 }
+        }
+    }
+
+    impl<I: Iterator<Item = (usize, T)>, T> Iterator for Shifted<I> {
+        type Item = (usize, T);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let (i, item) = self.iter.next()?;
+            Some((i + self.by, item))
+        }
+    }
+
+    impl<I: DoubleEndedIterator<Item = (usize, T)>, T> DoubleEndedIterator for Shifted<I> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            let (i, item) = self.iter.next_back()?;
+            Some((i + self.by, item))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct CharIndices;
+
+    impl BorrowIter for CharIndices {
+        type Item<'a> = (usize, char);
+        type Iter<'a> = Shifted<str::CharIndices<'a>>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            Shifted::new(base.char_indices())
+        }
+    }
+
+    impl Pieces for CharIndices {
+        fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+            item.span(base)
+        }
+
+        fn resume<'a>(&self, base: &'a str, from: &Checkpoint) -> Self::Iter<'a> {
+            Shifted {
+                iter: base[from.front .. from.back].char_indices(),
+                by:   from.front,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct MatchIndices<P>(pub P);
+
+    impl<P: StrPattern> BorrowIter for MatchIndices<P> {
+        type Item<'a> = (usize, &'a str);
+        type Iter<'a> = Shifted<P::MatchIndices<'a>>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            Shifted::new(self.0.clone().match_indices_in(base))
+        }
+    }
+
+    impl<P: StrPattern> Pieces for MatchIndices<P> {
+        fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+            item.span(base)
+        }
+
+        fn resume<'a>(&self, base: &'a str, from: &Checkpoint) -> Self::Iter<'a> {
+            Shifted {
+                iter: self.0.clone().match_indices_in(&base[from.front .. from.back]),
+                by:   from.front,
+            }
+        }
+    }
+
+    // How many pieces a `splitn` with limit `n` has left to give, counting
+    // the empty piece that `SelfRef` drops after resuming.
+    fn pieces_left(n: usize, taken: usize) -> usize {
+        if taken == 0 {
+            n
+        } else {
+            n.saturating_sub(taken) + 1
+        }
+    }
+
+    macro_rules! plain_splitter {
+        ($($name:ident => $iter:ident, $item:ty, $method:ident
+           $(, resume: $resume:expr)? $(, trim: $trim:ident)?;)*) => {$(
+            #[derive(Clone, Debug)]
+            pub struct $name;
+
+            impl BorrowIter for $name {
+                type Item<'a> = $item;
+                type Iter<'a> = str::$iter<'a>;
+
+                fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                    base.$method()
+                }
+            }
+
+            impl Pieces for $name {
+                fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                    item.span(base)
+                }
+
+                $(
+                fn resume<'a>(&self, base: &'a str, from: &Checkpoint)
+                              -> Self::Iter<'a> {
+                    $resume(self, base, from)
+                }
+                )?
+
+                $(
+                fn trim(&self, base: &str, from: &Checkpoint) -> (usize, usize) {
+                    $trim(base, from)
+                }
+                )?
+            }
+        )*};
+    }
+
+    plain_splitter! {
+        Lines => Lines, &'a str, lines, trim: trim_line_breaks;
+        SplitAsciiWhitespace => SplitAsciiWhitespace, &'a str, split_ascii_whitespace,
+            trim: trim_ascii_whitespace;
+    }
+
+    macro_rules! pattern_splitter {
+        ($($name:ident($($n:ident: $n_ty:ty),*) => $item:ty, $method:ident,
+           reversed: $rev:expr $(, resume: $resume:expr)? $(, trim: $trim:ident)?;)*) => {$(
+            #[derive(Clone, Debug)]
+            pub struct $name<P>($(pub $n_ty,)* pub P);
+
+            impl<P: StrPattern> BorrowIter for $name<P> {
+                type Item<'a> = $item;
+                type Iter<'a> = P::$name<'a>;
+
+                fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                    let $name($($n,)* pat) = self;
+                    pat.clone().$method($(*$n,)* base)
+                }
+            }
+
+            impl<P: StrPattern> Pieces for $name<P> {
+                const REVERSED: bool = $rev;
+
+                fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                    item.span(base)
+                }
+
+                $(
+                fn resume<'a>(&self, base: &'a str, from: &Checkpoint)
+                              -> Self::Iter<'a> {
+                    $resume(self, base, from)
+                }
+                )?
+
+                $(
+                fn trim(&self, base: &str, from: &Checkpoint) -> (usize, usize) {
+                    let $name(.., pat) = self;
+                    $trim(pat, base, from)
+                }
+                )?
+            }
+        )*};
+    }
+
+    pattern_splitter! {
+        Split() => &'a str, split_in, reversed: false, trim: trim_delimiters;
+        RSplit() => &'a str, rsplit_in, reversed: true, trim: trim_delimiters;
+        SplitTerminator() => &'a str, split_terminator_in, reversed: false,
+            trim: trim_delimiters;
+        SplitN(n: usize) => &'a str, splitn_in, reversed: false,
+            resume: |this: &Self, base: &'a str, from: &Checkpoint| {
+                let SplitN(n, pat) = this;
+                let n = pieces_left(*n, from.taken_front);
+                pat.clone().splitn_in(n, &base[from.front .. from.back])
+            },
+            trim: trim_delimiters;
+        RSplitN(n: usize) => &'a str, rsplitn_in, reversed: true,
+            resume: |this: &Self, base: &'a str, from: &Checkpoint| {
+                let RSplitN(n, pat) = this;
+                let n = pieces_left(*n, from.taken_back);
+                pat.clone().rsplitn_in(n, &base[from.front .. from.back])
+            },
+            trim: trim_delimiters;
+        SplitInclusive() => &'a str, split_inclusive_in, reversed: false;
+        Matches() => &'a str, matches_in, reversed: false;
+    }
+
+    #[cfg(feature = "split_pattern")]
+    mod any_regex {
+        use stable_deref_trait::StableDeref;
+
+        use super::{Checkpoint, Piece, Pieces};
+        use crate::haystack::Haystack;
+        use crate::self_ref::Splitter;
+        use crate::split_pattern::{Matches, Split, SplitKeep, SplitPattern};
+
+        #[derive(Clone, Debug)]
+        pub struct Regex<R>(pub R);
+
+        // The split borrows the regex through `R`, never from `self` itself.
+        unsafe impl<T, R> Splitter<T> for Regex<R>
+        where
+            T: ?Sized + Haystack,
+            R: StableDeref,
+            R::Target: SplitPattern<Text = T> + 'static, {
+
+            type Item<'a> = &'a T;
+            type Iter<'a> = Split<'a, 'a, R::Target>;
+
+            fn split<'a>(&self, base: &'a T) -> Self::Iter<'a> {
+                // SAFETY: `SelfRef` keeps `self`, and hence the regex, alive
+                // and in place for as long as the iterator.
+                let regex: &'a R::Target = unsafe { &*(&*self.0 as *const _) };
+                Split::new(regex, base)
+            }
+        }
+
+        impl<T, R> Pieces<T> for Regex<R>
+        where
+            T: ?Sized + Haystack,
+            for<'a> &'a T: Piece<T>,
+            R: StableDeref,
+            R::Target: SplitPattern<Text = T> + 'static, {
+
+            fn span(base: &T, item: &Self::Item<'_>) -> (usize, usize) {
+                item.span(base)
+            }
+
+            // Keeps the whole text around the remainder, for anchors and
+            // word boundaries.
+            fn resume<'a>(&self, base: &'a T, from: &Checkpoint)
+                          -> Self::Iter<'a> {
+                // SAFETY: As in `split`.
+                let regex: &'a R::Target = unsafe { &*(&*self.0 as *const _) };
+                Split::within(regex, base, from.front, from.back)
+            }
+
+            // Splits off the empty pieces that begin and end the remainder,
+            // which takes the delimiters next to them too.
+            fn trim(&self, base: &T, from: &Checkpoint) -> (usize, usize) {
+                let mut split = Split::within(&*self.0, base, from.front, from.back);
+                if from.taken_front > 0 {
+                    split.next();
+                }
+                if from.taken_back > 0 {
+                    split.next_back();
+                }
+                split.remainder().span(base)
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Keep<R>(pub R);
+
+        unsafe impl<R> Splitter for Keep<R>
+        where
+            R: StableDeref,
+            R::Target: SplitPattern<Text = str> + 'static, {
+
+            type Item<'a> = &'a str;
+            type Iter<'a> = SplitKeep<'a, 'a, R::Target>;
+
+            fn split<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                // SAFETY: As for `Regex`.
+                let regex: &'a R::Target = unsafe { &*(&*self.0 as *const _) };
+                SplitKeep::new(regex, base)
+            }
+        }
+
+        impl<R> Pieces for Keep<R>
+        where
+            R: StableDeref,
+            R::Target: SplitPattern<Text = str> + 'static, {
+
+            const EXACT_RESUME: bool = true;
+
+            fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                item.span(base)
+            }
+
+            // A delimiter may give several groups, so the offsets alone
+            // don’t say where to carry on; `SplitKeep::replay` explains.
+            fn resume<'a>(&self, base: &'a str, from: &Checkpoint)
+                          -> Self::Iter<'a> {
+                // SAFETY: As for `Regex`.
+                let regex: &'a R::Target = unsafe { &*(&*self.0 as *const _) };
+                SplitKeep::replay(regex, base, from.taken_front, from.taken_back)
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Find<R>(pub R);
+
+        unsafe impl<R> Splitter for Find<R>
+        where
+            R: StableDeref,
+            R::Target: SplitPattern<Text = str> + 'static, {
+
+            type Item<'a> = <R::Target as SplitPattern>::Match<'a>;
+            type Iter<'a> = Matches<'a, 'a, R::Target>;
+
+            fn split<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                // SAFETY: As for `Regex`.
+                let regex: &'a R::Target = unsafe { &*(&*self.0 as *const _) };
+                Matches::within(regex, base, 0, base.len())
+            }
+        }
+
+        impl<R> Pieces for Find<R>
+        where
+            R: StableDeref,
+            R::Target: SplitPattern<Text = str> + 'static, {
+
+            fn span(_base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                R::Target::match_span(item)
+            }
+
+            fn resume<'a>(&self, base: &'a str, from: &Checkpoint)
+                          -> Self::Iter<'a> {
+                // SAFETY: As for `Regex`.
+                let regex: &'a R::Target = unsafe { &*(&*self.0 as *const _) };
+                Matches::within(regex, base, from.front, from.back)
+            }
         }
     }
 
     #[cfg(feature = "regex")]
-    fn into_split_regex_ref_and_then<F, I>(self, regex: &re::Regex, fun: F)
-                                           -> IntoSplitRegexRefAndThen<Self, F, I>
-    where
-        F: FnMut(&str) -> I,
-        I: IntoIterator, {
+    mod regex_only {
+        use std::sync::Arc;
+
+        use stable_deref_trait::StableDeref;
+
+        use super::{BorrowIter, Checkpoint, Pieces};
+        use crate::lex::{Lexer, Token, Tokens};
+        use crate::re;
+        use crate::self_ref::Splitter;
+
+        #[derive(Clone, Debug)]
+        pub struct Lex<K>(pub Lexer<K>);
+
+        impl<K: Clone> BorrowIter for Lex<K> {
+            type Item<'a> = Token<K, &'a str>;
+            type Iter<'a> = Tokens<'a, K>;
+
+            fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                self.0.tokens(base)
+            }
+        }
+
+        impl<K: Clone> Pieces for Lex<K> {
+            fn span(_base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                (item.span.start, item.span.end)
+            }
+
+            fn resume<'a>(&self, base: &'a str, from: &Checkpoint)
+                          -> Self::Iter<'a> {
+                Tokens::within(&self.0, base, from.front, from.back)
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Captures<R> {
+            regex:     R,
+            // Shared by every `OwnedCaptures`.
+            pub names: Arc<[Option<Box<str>>]>,
+        }
+
+        impl<R: StableDeref<Target = re::Regex>> Captures<R> {
+            pub fn new(regex: R) -> Self {
+                let names = regex.capture_names().map(|n| n.map(Box::from)).collect();
+                Captures { regex, names }
+            }
+
+            /// Borrows the regex for as long as the caller chooses.
+            ///
+            /// # Safety
+            ///
+            /// The regex must stay alive and in place for all of `'a`, as it
+            /// does while `SelfRef` keeps `self` around the iterator.
+            unsafe fn regex<'a>(&self) -> &'a re::Regex {
+                &*(&*self.regex as *const _)
+            }
+        }
+
+        // As for `Regex`, the iterator borrows only through `R`.
+        unsafe impl<R: StableDeref<Target = re::Regex>> Splitter for Captures<R> {
+            type Item<'a> = regex::Captures<'a>;
+            type Iter<'a> = re::CaptureMatches<'a, 'a>;
+
+            fn split<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                // SAFETY: As for `Regex`.
+                let regex = unsafe { self.regex() };
+                re::CaptureMatches::within(regex, base, 0, base.len())
+            }
+        }
+
+        impl<R: StableDeref<Target = re::Regex>> Pieces for Captures<R> {
+            fn span(_base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                let whole = item.get(0).expect("group 0 always matches");
+                (whole.start(), whole.end())
+            }
+
+            fn resume<'a>(&self, base: &'a str, from: &Checkpoint)
+                          -> Self::Iter<'a> {
+                // SAFETY: As for `Regex`.
+                let regex = unsafe { self.regex() };
+                re::CaptureMatches::within(regex, base, from.front, from.back)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IntoSplitWhitespace<S: IntoSplitIter> {
+    inner: inner::WhitespaceIter<S>,
+}
+
+impl<S: IntoSplitIter> Iterator for IntoSplitWhitespace<S> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_map(str::to_owned)
+    }
+}
+
+impl<S: IntoSplitIter> DoubleEndedIterator for IntoSplitWhitespace<S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back_map(str::to_owned)
+    }
+}
+
+impl<S: IntoSplitIter> IntoSplitWhitespace<S> {
+    owner_methods!(into_split_whitespace [] ());
+}
+
+#[derive(Clone, Debug)]
+pub struct IntoSplitWhitespaceMap<S: IntoSplitIter, F> {
+    inner: inner::WhitespaceIter<S>,
+    fun:   F,
+}
+
+impl<S, F, R> Iterator for IntoSplitWhitespaceMap<S, F>
+where
+    S: IntoSplitIter,
+    F: FnMut(&str) -> R,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_map(&mut self.fun)
+    }
+}
+
+impl<S, F, R> DoubleEndedIterator for IntoSplitWhitespaceMap<S, F>
+where
+    S: IntoSplitIter,
+    F: FnMut(&str) -> R,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back_map(&mut self.fun)
+    }
+}
+
+impl<S: IntoSplitIter, F> IntoSplitWhitespaceMap<S, F> {
+    owner_methods!(into_split_whitespace_map [R] (fun: F)
+                   where [F: FnMut(&str) -> R]);
+}
+
+#[derive(Clone, Debug)]
+pub struct IntoSplitWhitespaceAndThen<S, F, I>
+where
+    S: IntoSplitIter,
+    F: FnMut(&str) -> I,
+    I: IntoIterator, {
+
+    inner: inner::WhitespaceIter<S>,
+    fun:   F,
+    rest:  Option<I::IntoIter>,
+}
+
+impl<S, F, I> Iterator for IntoSplitWhitespaceAndThen<S, F, I>
+where
+    S: IntoSplitIter,
+    F: FnMut(&str) -> I,
+    I: IntoIterator, {
+
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            if let Some(result) = self.rest.as_mut().and_then(Iterator::next) {
+                return Some(result);
+            }
+
+            self.rest = Some(self.inner.next_map(&mut self.fun)?.into_iter());
+        }
+    }
+}
+
+impl<S, F, I> IntoSplitWhitespaceAndThen<S, F, I>
+where
+    S: IntoSplitIter,
+    F: FnMut(&str) -> I,
+    I: IntoIterator, {
+
+    owner_methods!(into_split_whitespace_and_then [] (fun: F)
+                   where [F: FnMut(&str) -> I], clear rest);
+}
+
+into_split_types! {
+    IntoSplit, IntoSplitMap, IntoSplitAndThen [P: StrPattern]
+        = inner::Split<P>, &str => String, str::to_owned;
+        back if P::Split<'a>;
+        new into_split, into_split_map, into_split_and_then (pat: P);
+
+    IntoRSplit, IntoRSplitMap, IntoRSplitAndThen [P: StrPattern]
+        = inner::RSplit<P>, &str => String, str::to_owned;
+        back if P::RSplit<'a>;
+        new into_rsplit, into_rsplit_map, into_rsplit_and_then (pat: P);
+
+    IntoSplitTerminator, IntoSplitTerminatorMap, IntoSplitTerminatorAndThen
+        [P: StrPattern]
+        = inner::SplitTerminator<P>, &str => String, str::to_owned;
+        back if P::SplitTerminator<'a>;
+        new into_split_terminator, into_split_terminator_map,
+            into_split_terminator_and_then (pat: P);
+
+    IntoSplitN, IntoSplitNMap, IntoSplitNAndThen [P: StrPattern]
+        = inner::SplitN<P>, &str => String, str::to_owned;
+        back if P::SplitN<'a>;
+        new into_splitn, into_splitn_map,
+            into_splitn_and_then (n: usize, pat: P);
+
+    IntoRSplitN, IntoRSplitNMap, IntoRSplitNAndThen [P: StrPattern]
+        = inner::RSplitN<P>, &str => String, str::to_owned;
+        back if P::RSplitN<'a>;
+        new into_rsplitn, into_rsplitn_map,
+            into_rsplitn_and_then (n: usize, pat: P);
+
+    IntoSplitInclusive, IntoSplitInclusiveMap, IntoSplitInclusiveAndThen
+        [P: StrPattern]
+        = inner::SplitInclusive<P>, &str => String, str::to_owned;
+        back if P::SplitInclusive<'a>;
+        new into_split_inclusive, into_split_inclusive_map,
+            into_split_inclusive_and_then (pat: P);
+
+    IntoMatches, IntoMatchesMap, IntoMatchesAndThen [P: StrPattern]
+        = inner::Matches<P>, &str => String, str::to_owned;
+        back if P::Matches<'a>;
+        new into_matches, into_matches_map, into_matches_and_then (pat: P);
+
+    IntoMatchIndices, IntoMatchIndicesMap, IntoMatchIndicesAndThen [P: StrPattern]
+        = inner::MatchIndices<P>, (usize, &str) => (usize, String),
+          |(i, s): (usize, &str)| (i, s.to_owned());
+        back if inner::Shifted<P::MatchIndices<'a>>;
+        new into_match_indices, into_match_indices_map,
+            into_match_indices_and_then (pat: P);
+
+    IntoLines, IntoLinesMap, IntoLinesAndThen []
+        = inner::Lines, &str => String, str::to_owned;
+        back if std::str::Lines<'a>;
+        new into_lines, into_lines_map, into_lines_and_then ();
+
+    IntoSplitAsciiWhitespace, IntoSplitAsciiWhitespaceMap,
+    IntoSplitAsciiWhitespaceAndThen []
+        = inner::SplitAsciiWhitespace, &str => String, str::to_owned;
+        back if std::str::SplitAsciiWhitespace<'a>;
+        new into_split_ascii_whitespace, into_split_ascii_whitespace_map,
+            into_split_ascii_whitespace_and_then ();
+
+    IntoCharIndices, IntoCharIndicesMap, IntoCharIndicesAndThen []
+        = inner::CharIndices, (usize, char) => (usize, char), |pair| pair;
+        back if inner::Shifted<std::str::CharIndices<'a>>;
+        new into_char_indices, into_char_indices_map,
+            into_char_indices_and_then ();
+}
+
+lend_str! {
+    impl [S: IntoSplitIter] IntoSplitWhitespace<S>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoSplit<S, P>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoRSplit<S, P>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoSplitTerminator<S, P>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoSplitN<S, P>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoRSplitN<S, P>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoSplitInclusive<S, P>;
+    impl [S: IntoSplitIter, P: StrPattern] IntoMatches<S, P>;
+    impl [S: IntoSplitIter] IntoLines<S>;
+    impl [S: IntoSplitIter] IntoSplitAsciiWhitespace<S>;
+}
+
+pub struct IntoIterWith<S: IntoSplitIter, P: BorrowIter, F> {
+    inner: SelfRef<S, P>,
+    fun:   F,
+}
+
+// Clones the iterator as it stands, since a `BorrowIter` can’t be resumed.
+impl<S, P, F> Clone for IntoIterWith<S, P, F>
+where
+    S: IntoSplitIter + CloneStableDeref,
+    P: BorrowIter + Clone,
+    for<'a> P::Iter<'a>: Clone,
+    F: Clone, {
+
+    fn clone(&self) -> Self {
+        IntoIterWith {
+            inner: self.inner.clone_iter(),
+            fun:   self.fun.clone(),
+        }
+    }
+}
+
+impl<S, P, F> fmt::Debug for IntoIterWith<S, P, F>
+where
+    S: IntoSplitIter + fmt::Debug,
+    P: BorrowIter + fmt::Debug, {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IntoIterWith")
+         .field("inner", &self.inner)
+         .finish()
+    }
+}
+
+impl<S, P, F, R> Iterator for IntoIterWith<S, P, F>
+where
+    S: IntoSplitIter,
+    P: BorrowIter,
+    F: for<'a> FnMut(P::Item<'a>) -> R, {
+
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let fun = &mut self.fun;
+        self.inner.rent_mut(|iter| iter.next().map(fun))
+    }
+}
+
+impl<S, P, F, R> DoubleEndedIterator for IntoIterWith<S, P, F>
+where
+    S: IntoSplitIter,
+    P: BorrowIter,
+    F: for<'a> FnMut(P::Item<'a>) -> R,
+    for<'a> P::Iter<'a>: DoubleEndedIterator, {
+
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let fun = &mut self.fun;
+        self.inner.rent_mut(|iter| iter.next_back().map(fun))
+    }
+}
 
-        IntoSplitRegexRefAndThen {
-            inner: self.into_split_regex_ref(regex).inner,
-            fun,
-            rest:  None,
-        }
+impl<S: IntoSplitIter, P: BorrowIter, F> IntoIterWith<S, P, F> {
+    /// Gives back the string, discarding the iterator.
+    pub fn into_inner(self) -> S {
+        self.inner.into_inner()
     }
 }
 
-impl<T: Deref<Target = str> + StableDeref + Sized> IntoSplitIter for T { }
+#[cfg(feature = "split_pattern")]
+into_split_types! {
+    IntoSplitRegex, IntoSplitRegexMap, IntoSplitRegexAndThen [R: IntoRegex]
+        = inner::Regex<R::StableRegex>, &str => String, str::to_owned;
+        back if split_pattern::Split<'a, 'a, R::Regex>;
+        new into_split_regex, into_split_regex_map,
+            into_split_regex_and_then (regex: R);
+}
 
-struct PhantomLifetime<'a, T> {
-    base: T,
-    _marker: std::marker::PhantomData<&'a ()>,
+#[cfg(feature = "regex")]
+into_split_types! {
+    for IntoSplitBytesIter => [u8];
+
+    IntoSplitBytesRegex, IntoSplitBytesRegexMap, IntoSplitBytesRegexAndThen
+        [R: IntoRegex<[u8]>]
+        = inner::Regex<R::StableRegex>, &[u8] => Vec<u8>, <[u8]>::to_vec;
+        back if split_pattern::Split<'a, 'a, R::Regex>;
+        new into_split_regex, into_split_regex_map,
+            into_split_regex_and_then (regex: R);
 }
 
-impl<T: fmt::Debug> fmt::Debug for PhantomLifetime<'_, T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.base.fmt(f)
-    }
+#[cfg(feature = "split_pattern")]
+into_split_types! {
+    IntoFindIter, IntoFindIterMap, IntoFindIterAndThen [R: IntoRegex]
+        = inner::Find<R::StableRegex>,
+          <R::Regex as SplitPattern>::Match<'_> => (Range<usize>, String),
+          |m: <R::Regex as SplitPattern>::Match<'_>| {
+              let (start, end) = R::Regex::match_span(&m);
+              (start .. end, R::Regex::match_text(&m).to_owned())
+          };
+        back if split_pattern::Matches<'a, 'a, R::Regex>;
+        new into_find_iter, into_find_iter_map, into_find_iter_and_then (regex: R);
 }
 
-impl<T> Deref for PhantomLifetime<'_, T>
-where
-    T: Deref {
+#[cfg(feature = "split_pattern")]
+into_split_types! {
+    IntoSplitRegexKeep, IntoSplitRegexKeepMap, IntoSplitRegexKeepAndThen [R: IntoRegex]
+        = inner::Keep<R::StableRegex>, &str => String, str::to_owned;
+        back if split_pattern::SplitKeep<'a, 'a, R::Regex>;
+        new into_split_regex_keep, into_split_regex_keep_map,
+            into_split_regex_keep_and_then (regex: R);
+}
 
-    type Target = T::Target;
+#[cfg(feature = "regex")]
+into_split_types! {
+    IntoTokens, IntoTokensMap, IntoTokensAndThen [K: Clone]
+        = inner::Lex<K>, Token<K, &str> => Token<K>,
+          |token: Token<K, &str>| token.into_owned();
+        back if Tokens<'a, K>;
+        new into_tokens, into_tokens_map, into_tokens_and_then (lexer: Lexer<K>);
+}
 
-    fn deref(&self) -> &Self::Target {
-        self.base.deref()
+#[cfg(feature = "regex")]
+impl<S: IntoSplitIter, K: Clone> IntoTokens<S, K> {
+    /// Returns the next token without copying its text into a `String`.
+    /// The token borrows from the lexer, so it must be dropped before the
+    /// lexer advances again.
+    pub fn next_token(&mut self) -> Option<Token<K, &str>> {
+        self.inner.next_ref()
     }
 }
 
-unsafe impl<T> StableDeref for PhantomLifetime<'_, T>
-where
-    T: StableDeref { }
+#[cfg(feature = "split_pattern")]
+lend_str! {
+    impl [S: IntoSplitIter, R: IntoRegex] IntoSplitRegex<S, R>;
+}
 
-rental! {
-    #[allow(clippy::useless_transmute)]
-    mod inner {
-        use std::str;
-        use super::{IntoSplitIter, PhantomLifetime, re};
+#[cfg(feature = "split_pattern")]
+take_error! {
+    impl [S: IntoSplitIter, R: IntoRegex] IntoSplitRegex<S, R>;
+    impl [S: IntoSplitIter, R: IntoRegex, F] IntoSplitRegexMap<S, R, F>;
+    impl [S: IntoSplitIter, R: IntoRegex, F, I] IntoSplitRegexAndThen<S, R, F, I>
+        where [I: IntoIterator];
+    impl [S: IntoSplitIter, R: IntoRegex] IntoFindIter<S, R>;
+    impl [S: IntoSplitIter, R: IntoRegex, F] IntoFindIterMap<S, R, F>;
+    impl [S: IntoSplitIter, R: IntoRegex, F, I] IntoFindIterAndThen<S, R, F, I>
+        where [I: IntoIterator];
+    impl [S: IntoSplitIter, R: IntoRegex] IntoMatchSpans<S, R>;
+    impl [S: IntoSplitIter, R: IntoRegex] IntoSplitRegexKeep<S, R>;
+    impl [S: IntoSplitIter, R: IntoRegex, F] IntoSplitRegexKeepMap<S, R, F>;
+    impl [S: IntoSplitIter, R: IntoRegex, F, I] IntoSplitRegexKeepAndThen<S, R, F, I>
+        where [I: IntoIterator];
+}
 
-        #[rental(debug)]
-        pub struct Whitespace<S: IntoSplitIter> {
-            base:     S,
-            splitter: str::SplitWhitespace<'base>,
-        }
+#[cfg(feature = "split_pattern")]
+lend_str! {
+    impl [S: IntoSplitIter, R: IntoRegex] IntoSplitRegexKeep<S, R>;
+}
 
-        #[rental(debug)]
-        pub (super) struct RegexInto<S: IntoSplitIter, R: super::StableDeref>
-        where
-//            R::Target: 'regex
-        {
-            base:     S,
-            #[target_ty = "R::Target"]
-            regex:    R,
-            splitter: re::Split<'regex, 'base>,
-        }
+#[cfg(feature = "split_pattern")]
+#[derive(Clone, Debug)]
+pub struct IntoMatchSpans<S: IntoSplitIter, R: IntoRegex> {
+    inner: SelfRef<S, inner::Find<R::StableRegex>>,
+}
 
-        #[rental(debug)]
-        pub struct Regex<S: IntoSplitIter> {
-            base:     S,
-            regex:    Box<re::Regex>,
-            splitter: re::Split<'regex, 'base>,
-        }
+#[cfg(feature = "split_pattern")]
+impl<S, R> Iterator for IntoMatchSpans<S, R>
+where
+    S: IntoSplitIter,
+    R: IntoRegex, {
 
-        #[rental(debug)]
-        pub struct RegexRef<'r, S: IntoSplitIter> {
-            base:     S,
-            splitter: re::Split<'r, 'base>,
-        }
-    }
-}
+    type Item = Range<usize>;
 
-impl<S: IntoSplitIter> inner::Whitespace<S> {
-    fn next_map<R, F: FnOnce(&str) -> R>(&mut self, fun: F) -> Option<R> {
-        self.rent_mut(|iter| iter.next().map(fun))
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_map(|m| {
+            let (start, end) = R::Regex::match_span(&m);
+            start .. end
+        })
     }
 }
 
-impl<S: IntoSplitIter> inner::Regex<S> {
-    fn next_map<R, F: FnOnce(&str) -> R>(&mut self, fun: F) -> Option<R> {
-        self.rent_mut(|iter| iter.next().map(fun))
+#[cfg(feature = "split_pattern")]
+impl<S, R> DoubleEndedIterator for IntoMatchSpans<S, R>
+where
+    S: IntoSplitIter,
+    R: IntoRegex, {
+
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back_map(|m| {
+            let (start, end) = R::Regex::match_span(&m);
+            start .. end
+        })
     }
 }
 
-impl<'a, S: IntoSplitIter> inner::RegexRef<'a, S> {
-    fn next_map<R: 'a, F: FnOnce(&str) -> R>(&mut self, fun: F) -> Option<R> {
-        self.rent_mut(|iter| iter.next().map(fun))
-    }
+#[cfg(feature = "split_pattern")]
+impl<S: IntoSplitIter, R: IntoRegex> IntoMatchSpans<S, R> {
+    owner_methods!(into_match_spans [] (regex: R));
 }
 
-#[derive(Debug)]
-pub struct IntoSplitWhitespace<S: IntoSplitIter> {
-    inner: inner::Whitespace<S>,
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+pub struct IntoCapturesIter<S: IntoSplitIter, R: IntoRegex<Regex = re::Regex>> {
+    inner: SelfRef<S, inner::Captures<R::StableRegex>>,
 }
 
-impl<S: IntoSplitIter> Iterator for IntoSplitWhitespace<S> {
-    type Item = String;
+#[cfg(feature = "regex")]
+impl<S, R> Iterator for IntoCapturesIter<S, R>
+where
+    S: IntoSplitIter,
+    R: IntoRegex<Regex = re::Regex>, {
+
+    type Item = re::OwnedCaptures;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next_map(str::to_owned)
+        let names = Arc::clone(&self.inner.splitter().names);
+        self.inner.next_map(|caps| re::OwnedCaptures::new(&caps, &names))
     }
 }
 
-#[derive(Debug)]
-pub struct IntoSplitWhitespaceMap<S: IntoSplitIter, F> {
-    inner: inner::Whitespace<S>,
+#[cfg(feature = "regex")]
+impl<S: IntoSplitIter, R: IntoRegex<Regex = re::Regex>> IntoCapturesIter<S, R> {
+    owner_methods!(into_captures_iter [] (regex: R));
+}
+
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+pub struct IntoCapturesIterMap<S: IntoSplitIter, R: IntoRegex<Regex = re::Regex>, F> {
+    inner: SelfRef<S, inner::Captures<R::StableRegex>>,
     fun:   F,
 }
 
-impl<S, F, R> Iterator for IntoSplitWhitespaceMap<S, F>
+#[cfg(feature = "regex")]
+impl<S, R, F, B> Iterator for IntoCapturesIterMap<S, R, F>
 where
     S: IntoSplitIter,
-    F: FnMut(&str) -> R,
+    R: IntoRegex<Regex = re::Regex>,
+    F: FnMut(regex::Captures<'_>) -> B,
 {
-    type Item = R;
+    type Item = B;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next_map(&mut self.fun)
     }
 }
 
-#[derive(Debug)]
-pub struct IntoSplitWhitespaceAndThen<S, F, I>
+#[cfg(feature = "regex")]
+impl<S: IntoSplitIter, R: IntoRegex<Regex = re::Regex>, F> IntoCapturesIterMap<S, R, F> {
+    owner_methods!(into_captures_iter_map [B] (regex: R, fun: F)
+                   where [F: FnMut(regex::Captures<'_>) -> B]);
+}
+
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+pub struct IntoCapturesIterAndThen<S, R, F, I>
 where
     S: IntoSplitIter,
-    F: FnMut(&str) -> I,
+    R: IntoRegex<Regex = re::Regex>,
     I: IntoIterator, {
 
-    inner: inner::Whitespace<S>,
+    inner: SelfRef<S, inner::Captures<R::StableRegex>>,
     fun:   F,
     rest:  Option<I::IntoIter>,
 }
 
-impl<S, F, I> Iterator for IntoSplitWhitespaceAndThen<S, F, I>
+#[cfg(feature = "regex")]
+impl<S, R, F, I> Iterator for IntoCapturesIterAndThen<S, R, F, I>
 where
     S: IntoSplitIter,
-    F: FnMut(&str) -> I,
+    R: IntoRegex<Regex = re::Regex>,
+    F: FnMut(regex::Captures<'_>) -> I,
     I: IntoIterator, {
 
     type Item = I::Item;
 
     fn next(&mut self) -> Option<I::Item> {
-        while let Some(rest) = self.rest.as_mut() {
-            if let Some(result) = rest.next() {
+        loop {
+            if let Some(result) = self.rest.as_mut().and_then(Iterator::next) {
                 return Some(result);
             }
 
-            self.rest = self.inner
-                            .next_map(&mut self.fun)
-                            .map(I::into_iter);
+            self.rest = Some(self.inner.next_map(&mut self.fun)?.into_iter());
         }
-
-        None
     }
 }
 
 #[cfg(feature = "regex")]
-pub use regex_only::*;
-
-#[cfg(feature = "regex")]
-mod regex_only {
-    use super::*;
+impl<S, R, F, I> IntoCapturesIterAndThen<S, R, F, I>
+where
+    S: IntoSplitIter,
+    R: IntoRegex<Regex = re::Regex>,
+    I: IntoIterator, {
 
-    #[derive(Debug)]
-    pub struct IntoSplitRegex<S>
-    where
-        S: IntoSplitIter {
+    owner_methods!(into_captures_iter_and_then [] (regex: R, fun: F)
+                   where [F: FnMut(regex::Captures<'_>) -> I], clear rest);
+}
 
-        pub (super) inner: inner::Regex<S>,
-    }
+// Checks that rewinding a fresh splitter to a checkpoint taken after
+// any mix of `next` and `next_back` (if given) yields what is left.
+#[cfg(test)]
+macro_rules! assert_resumes {
+    ($make:expr) => {
+        assert_resumes!($make, |_| 0, next);
+    };
+
+    ($make:expr, double_ended) => {
+        assert_resumes!($make, |left| left, next_back);
+    };
+
+    ($make:expr, $max_back:expr, $next_back:ident) => {
+        let all: Vec<_> = $make.collect();
+        for front in 0 ..= all.len() {
+            for back in 0 ..= $max_back(all.len() - front) {
+                let mut iter = $make;
+                for _ in 0 .. front {
+                    iter.next();
+                }
+                let mut taken_back: Vec<_> =
+                    (0 .. back).map(|_| iter.$next_back().unwrap()).collect();
+                taken_back.reverse();
+
+                let mut resumed = $make;
+                resumed.rewind(iter.checkpoint());
+                assert_eq!(resumed.remainder(), iter.remainder());
+                let mut rest: Vec<_> = resumed.collect();
+                rest.extend(taken_back);
+
+                assert_eq!(&rest[..], &all[front ..],
+                           "{} after {} + {}", stringify!($make), front, back);
+            }
+        }
+    };
+}
 
-    #[derive(Debug)]
-    pub struct IntoSplitRegexRef<'a, S>
-    where
-        S: IntoSplitIter {
+#[cfg(all(test, feature = "regex"))]
+mod regex_tests {
+    use super::*;
+    use crate::{RegexSplit, SplitRegexBytes, SplitRegexKeep};
+    use std::io::{Read, BufRead, BufReader};
+    use std::rc::Rc;
+    use lazy_static::lazy_static;
 
-        pub (super) inner: inner::RegexRef<'a, S>,
+    #[test]
+    fn three_words() {
+        assert_words(
+            "one two three",
+            &["one", "two", "three"]
+        );
     }
 
-    #[derive(Debug)]
-    pub struct IntoSplitRegexMap<S, F>
-    where
-        S: IntoSplitIter {
-
-        pub (super) inner: inner::Regex<S>,
-        pub (super) fun:   F,
+    #[test]
+    fn with_punctuation() {
+        assert_words(
+            "one--two-two /three",
+            &["one", "two-two", "three"]
+        );
     }
 
-    #[derive(Debug)]
-    pub struct IntoSplitRegexRefMap<'a, S, F>
-    where
-        S: IntoSplitIter {
+    #[test]
+    fn multiple_lines() {
+        assert_words(
+            concat![
+            "first line\n",
+            "\n",
+            "above line was blank!\n",
+        ],
+            // Splitting the blank line gives one empty piece, as
+            // `str::split` does and `Regex::split` has since regex 1.2.
+            &["first", "line", "", "above", "line", "was", "blank!"]
+        );
+    }
 
-        pub (super) inner: inner::RegexRef<'a, S>,
-        pub (super) fun:   F,
+    #[test]
+    fn regex_reversed() {
+        let re = re::Regex::new(", *").unwrap();
+        let s = String::from(",a, b,,  c,");
+        assert_eq!( s.clone().into_split_regex(re.clone()).rev().collect::<Vec<_>>(),
+                    ownv(&["", "c", "", "b", "a", ""]) );
+        assert_eq!( s.into_split_regex_map(&re, str::len).rev().collect::<Vec<_>>(),
+                    vec![0, 1, 0, 1, 1, 0] );
     }
 
-    #[derive(Debug)]
-    pub struct IntoSplitRegexAndThen<S, F, I>
-    where
-        S: IntoSplitIter,
-        I: IntoIterator, {
+    #[test]
+    fn regex_both_ends() {
+        let re = re::Regex::new("-").unwrap();
+        let mut iter = String::from("a-b-c-d").into_split_regex(re);
+        assert_eq!( iter.next(), Some("a".to_owned()) );
+        assert_eq!( iter.next_back(), Some("d".to_owned()) );
+        assert_eq!( iter.next(), Some("b".to_owned()) );
+        assert_eq!( iter.next_back(), Some("c".to_owned()) );
+        assert_eq!( iter.next(), None );
+        assert_eq!( iter.next_back(), None );
+    }
 
-        pub (super) inner: inner::Regex<S>,
-        pub (super) fun:   F,
-        pub (super) rest:  Option<I::IntoIter>,
+    #[test]
+    fn regex_lending() {
+        let re = re::Regex::new(" +").unwrap();
+        let mut iter = String::from("a  b c").into_split_regex(&re);
+        assert_eq!( iter.next_str(), Some("a") );
+        assert_eq!( iter.try_fold_str(String::new(), |acc, s| Ok::<_, ()>(acc + s)),
+                    Ok("bc".to_owned()) );
     }
 
-    #[derive(Debug)]
-    pub struct IntoSplitRegexRefAndThen<'a, S, F, I>
-    where
-        S: IntoSplitIter,
-        I: IntoIterator, {
+    #[test]
+    fn regex_remainder() {
+        let re = re::Regex::new(" *; *").unwrap();
+        let mut iter = String::from("a ; b;c").into_split_regex(re);
+        iter.next();
+        assert_eq!( (iter.remainder(), iter.offset()), ("b;c", 4) );
+        assert_eq!( &"a ; b;c"[iter.offset() ..], iter.remainder() );
+        iter.next_back();
+        assert_eq!( iter.remainder(), "b" );
+        assert_eq!( iter.into_inner(), "a ; b;c" );
+    }
 
-        pub (super) inner: inner::RegexRef<'a, S>,
-        pub (super) fun:   F,
-        pub (super) rest:  Option<I::IntoIter>,
+    // Checks that the owning iterator `$make` builds from each pattern and
+    // text in one shared table yields `$expected` once mapped by `$view`,
+    // and resumes from every checkpoint. Miri is too slow for the whole
+    // table, so it takes the first few of each.
+    macro_rules! assert_regex_resumes {
+        (|$re:ident, $text:ident| $make:expr, $view:expr, $expected:expr
+         $(, $double_ended:ident)?) => {
+            let take = if cfg!(miri) { 3 } else { usize::MAX };
+            let patterns = ["", " *(,)( )?", "(^ *)|(,)", r"\b", "(x)*", r"(?m)$"];
+            for pattern in patterns.iter().take(take) {
+                let $re = &re::Regex::new(pattern).unwrap();
+                let texts = ["", "é,€ x", "x  y", "a", ", a,b ,, c", "one two"];
+                for &$text in texts.iter().take(take) {
+                    let all: Vec<_> = $make.map($view).collect();
+                    assert_eq!( all, $expected, "{:?} on {:?}", pattern, $text );
+
+                    assert_resumes!($make $(, $double_ended)?);
+                }
+            }
+        };
     }
 
+    #[test]
+    fn regex_resume() {
+        use std::convert::identity;
+
+        assert_regex_resumes!(|re, text| text.into_split_regex(re), identity,
+                              re.split(text).map(str::to_owned).collect::<Vec<_>>(),
+                              double_ended);
+        assert_regex_resumes!(|re, text| text.into_split_regex_keep(re), identity,
+                              text.split_regex_keep(re).map(str::to_owned).collect::<Vec<_>>(),
+                              double_ended);
+        assert_regex_resumes!(|re, text| text.into_match_spans(re), identity,
+                              re.find_iter(text).map(|m| m.range()).collect::<Vec<_>>(),
+                              double_ended);
+        assert_regex_resumes!(|re, text| text.into_captures_iter(re),
+                              |caps: re::OwnedCaptures| caps.span(0).unwrap(),
+                              re.find_iter(text).map(|m| m.range()).collect::<Vec<_>>());
+    }
 
-    impl<S: IntoSplitIter> Iterator for IntoSplitRegex<S> {
-        type Item = String;
+    #[test]
+    fn captures_iter() {
+        let re = re::Regex::new(r"(?P<key>\w+)=(?P<value>\w*)(;)?").unwrap();
+        let text = String::from("a=1; bc=; é=23;");
+        let expected: Vec<Vec<_>> = re.captures_iter(&text)
+            .map(|caps| caps.iter().map(|m| m.map(|m| m.as_str().to_owned())).collect())
+            .collect();
+
+        let caps: Vec<_> = text.clone().into_captures_iter(&re).collect();
+        assert_eq!( caps.len(), 3 );
+        assert_eq!( caps[0].as_str(), "a=1;" );
+        assert_eq!( &caps[1]["key"], "bc" );
+        assert_eq!( &caps[1][2], "" );
+        assert_eq!( caps[2].name("value"), Some("23") );
+        assert_eq!( caps[2].name_span("key"), Some(10 .. 12) );
+        assert_eq!( caps[2].span(3), Some(15 .. 16) );
+        assert_eq!( caps[2].get(4), None );
+        assert_eq!( caps[2].name("nope"), None );
+
+        let mapped: Vec<Vec<_>> = text.into_captures_iter_map(&re, |caps| {
+            caps.iter().map(|m| m.map(|m| m.as_str().to_owned())).collect()
+        }).collect();
+        assert_eq!( mapped, expected );
+
+        let keys: Vec<_> = "x=1 y=2".into_captures_iter_and_then(&re, |caps| {
+            vec![caps["key"].to_owned(), caps["value"].to_owned()]
+        }).collect();
+        assert_eq!( keys, ownv(&["x", "1", "y", "2"]) );
+    }
 
-        fn next(&mut self) -> Option<Self::Item> {
-            self.inner.next_map(str::to_owned)
-        }
+    #[test]
+    #[should_panic(expected = "no group named 'nope'")]
+    fn captures_missing_name() {
+        let re = re::Regex::new("(?P<a>a)").unwrap();
+        let caps = "a".into_captures_iter(re).next().unwrap();
+        let _ = &caps["nope"];
     }
 
-    impl<'r, S: IntoSplitIter> Iterator for IntoSplitRegexRef<'r, S> {
-        type Item = String;
+    #[test]
+    fn split_position() {
+        let re = re::Regex::new(r"\s+").unwrap();
+        let mut iter = RegexSplit::new(&re, "ab  c d");
+        assert_eq!( iter.position(), 0 );
+        assert_eq!( iter.next(), Some("ab") );
+        assert_eq!( (iter.position(), iter.remainder()), (4, "c d") );
+        assert_eq!( iter.next_back(), Some("d") );
+        assert_eq!( (iter.position(), iter.remainder()), (4, "c") );
+        assert_eq!( iter.regex().as_str(), r"\s+" );
+        assert_eq!( iter.next(), Some("c") );
+        assert_eq!( iter.remainder(), "" );
+        assert_eq!( iter.next(), None );
+
+        let tab = re::BytesRegex::new("\t").unwrap();
+        let mut fields = (&b"a\tbc"[..]).split_regex(&tab);
+        fields.next();
+        assert_eq!( (fields.position(), fields.remainder()), (2, &b"bc"[..]) );
+        assert_eq!( format!("{:?}", fields),
+                    r#"BytesSplit(Split { pattern: "\t", position: 2 })"# );
+
+        let mut owned = "ab  c d".into_split_regex(&re);
+        owned.next();
+        assert_eq!( owned.checkpoint().front, 2 );
+        assert_eq!( (owned.offset(), owned.remainder()), (4, "c d") );
+    }
 
-        fn next(&mut self) -> Option<Self::Item> {
-            self.inner.next_map(str::to_owned)
-        }
+    #[test]
+    fn into_tokens() {
+        let lexer = Lexer::new(vec![("word", r"\w+"), ("space", r"\s+")]).unwrap();
+        let text: Rc<str> = "ab  c!d".into();
+
+        let tokens: Vec<_> = Rc::clone(&text).into_tokens(lexer.clone()).collect();
+        assert_eq!( tokens[1], Token { kind: Some("space"), text: "  ".to_owned(), span: 2 .. 4 } );
+        assert_eq!( tokens[3], Token { kind: None, text: "!".to_owned(), span: 5 .. 6 } );
+
+        let mut iter = Rc::clone(&text).into_tokens(lexer.clone());
+        assert_eq!( iter.next_token().map(|t| t.text), Some("ab") );
+        assert_eq!( iter.remainder(), "  c!d" );
+        let rest: Vec<_> = IntoTokens::resume_from(Rc::clone(&text), lexer.clone(),
+                                                   iter.checkpoint())
+            .collect();
+        assert_eq!( rest, &tokens[1 ..] );
+
+        let kinds: Vec<_> = text.into_tokens_map(lexer, |t| t.kind).collect();
+        assert_eq!( kinds, [Some("word"), Some("space"), Some("word"), None, Some("word")] );
     }
 
-    impl<S, F, R> Iterator for IntoSplitRegexMap<S, F>
-    where
-        S: IntoSplitIter,
-        F: FnMut(&str) -> R {
+    #[test]
+    fn split_keep() {
+        let cases: &[(&str, &str, &[&str])] = &[
+            ("([+*])", "1+2*3", &["1", "+", "2", "*", "3"]),
+            (r"(\+)|(\*)", "1+2*3", &["1", "+", "2", "*", "3"]),
+            (",", "a,b,", &["a", ",", "b", ",", ""]),
+            ("(,)( )?", "a, b,c", &["a", ",", " ", "b", ",", "c"]),
+            ("(x*)", "ab", &["", "", "a", "", "b", "", ""]),
+            ("x", "", &[""]),
+        ];
+
+        for &(pattern, text, expected) in cases {
+            let re = re::Regex::new(pattern).unwrap();
+            assert_eq!( text.split_regex_keep(&re).collect::<Vec<_>>(), expected,
+                        "{:?} on {:?}", pattern, text );
+            assert!( text.split_regex_keep(&re).rev().eq(expected.iter().rev().copied()) );
+            assert_eq!( text.into_split_regex_keep(&re).collect::<Vec<_>>(),
+                        ownv(expected) );
+        }
 
-        type Item = R;
+        let re = re::Regex::new("([+*])").unwrap();
+        let mut iter = "1+2*3".split_regex_keep(&re);
+        assert_eq!( iter.next(), Some("1") );
+        assert_eq!( iter.next_back(), Some("3") );
+        assert_eq!( iter.next_back(), Some("*") );
+        assert_eq!( iter.collect::<Vec<_>>(), ["+", "2"] );
+    }
 
-        fn next(&mut self) -> Option<Self::Item> {
-            self.inner.next_map(&mut self.fun)
-        }
+    #[test]
+    fn find_iter() {
+        let re = re::Regex::new(r"\d+").unwrap();
+        let text = String::from("é 12 x 345 6");
+
+        let found: Vec<_> = text.clone().into_find_iter(&re).collect();
+        assert_eq!( found, vec![(3 .. 5, "12".to_owned()),
+                                (8 .. 11, "345".to_owned()),
+                                (12 .. 13, "6".to_owned())] );
+
+        let mut spans = text.clone().into_match_spans(&re);
+        assert_eq!( spans.next_back(), Some(12 .. 13) );
+        assert_eq!( spans.remainder(), "é 12 x 345 " );
+        assert_eq!( spans.collect::<Vec<_>>(), vec![3 .. 5, 8 .. 11] );
+
+        let redacted: String = text.into_find_iter_map(&re, |m: regex::Match<'_>| m.start())
+            .rev()
+            .map(|start| start.to_string())
+            .collect();
+        assert_eq!( redacted, "1283" );
     }
 
-    impl<'r, S, F, R: 'r> Iterator for IntoSplitRegexRefMap<'r, S, F>
-    where
-        S: IntoSplitIter,
-        F: FnMut(&str) -> R {
+    #[test]
+    fn regex_clone() {
+        let re = re::Regex::new(r"\b").unwrap();
+        let mut iter = Rc::<str>::from("ab cd").into_split_regex(re);
+        iter.next();
+        iter.next_back();
+        let fork = iter.clone();
+        assert_eq!( fork.collect::<Vec<_>>(), ownv(&["ab", " ", "cd"]) );
+        assert_eq!( iter.next(), Some("ab".to_owned()) );
+    }
 
-        type Item = R;
+    #[test]
+    fn any_into_regex() {
+        lazy_static! {
+            static ref DASH: re::Regex = re::Regex::new("-").unwrap();
+        }
 
-        fn next(&mut self) -> Option<Self::Item> {
-            self.inner.next_map(&mut self.fun)
+        let dash = || DASH.clone();
+        let shared = std::sync::Arc::new(dash());
+        let expected = ownv(&["a", "b"]);
+        assert_eq!( "a-b".into_split_regex(dash()).collect::<Vec<_>>(), expected );
+        assert_eq!( "a-b".into_split_regex(&*DASH).collect::<Vec<_>>(), expected );
+        assert_eq!( "a-b".into_split_regex(Box::new(dash())).collect::<Vec<_>>(),
+                    expected );
+        assert_eq!( "a-b".into_split_regex(Rc::new(dash())).collect::<Vec<_>>(),
+                    expected );
+
+        for _ in 0 .. 2 {
+            let lengths = String::from("a-bb")
+                .into_split_regex_map(std::sync::Arc::clone(&shared), str::len);
+            assert_eq!( lengths.collect::<Vec<_>>(), vec![1, 2] );
         }
+        assert_eq!( std::sync::Arc::strong_count(&shared), 1 );
     }
 
-    impl<S, F, I> Iterator for IntoSplitRegexAndThen<S, F, I>
-    where
-        S: IntoSplitIter,
-        F: FnMut(&str) -> I,
-        I: IntoIterator {
-
-        type Item = I::Item;
+    #[test]
+    fn split_pattern() {
+        let lines = ["a;b", "c ; d", "e"];
+        let pieces: Vec<Vec<_>> = lines.iter()
+            .map(|line| line.into_split_pattern(" *; *").unwrap().collect())
+            .collect();
+        assert_eq!( pieces, vec![ownv(&["a", "b"]), ownv(&["c", "d"]), ownv(&["e"])] );
+
+        assert!( "x".into_split_pattern("(").is_err() );
+    }
 
-        fn next(&mut self) -> Option<Self::Item> {
-            while let Some(rest) = self.rest.as_mut() {
-                if let Some(result) = rest.next() {
-                    return Some(result);
-                }
+    #[test]
+    fn split_bytes() {
+        let tab = re::BytesRegex::new("\t").unwrap();
+        let row = b"caf\xe9\t\xff\t\tend".to_vec();
+        let expected: Vec<_> = tab.split(&row).map(<[u8]>::to_vec).collect();
+        assert_eq!( expected.len(), 4 );
+
+        assert_eq!( row.clone().into_split_regex(&tab).collect::<Vec<_>>(), expected );
+        assert_eq!( Box::<[u8]>::from(&row[..]).into_split_regex(tab.clone())
+                        .rev().collect::<Vec<_>>(),
+                    expected.iter().rev().cloned().collect::<Vec<_>>() );
+        assert_eq!( Rc::<[u8]>::from(&row[..]).into_split_regex_map(&tab, <[u8]>::len)
+                        .collect::<Vec<_>>(),
+                    vec![4, 1, 0, 3] );
+
+        let mut iter = row.into_split_regex(Rc::new(tab));
+        iter.next();
+        assert_eq!( iter.remainder(), b"\xff\t\tend" );
+        assert_eq!( iter.offset(), 5 );
+    }
 
-                self.rest = self.inner
-                                .next_map(&mut self.fun)
-                                .map(I::into_iter);
+    #[test]
+    fn split_bytes_borrowed() {
+        for pattern in &["", ",", "(?-u:\\xff)*", "\\b", "^|$"] {
+            let regex = re::BytesRegex::new(pattern).unwrap();
+            for text in &[&b""[..], b"a", b"\xff,\xfe,", b"\xe2\x82\xac a"] {
+                let text = *text;
+                let expected: Vec<_> = regex.split(text).collect();
+                assert_eq!( text.split_regex(&regex).collect::<Vec<_>>(), expected,
+                            "{:?} on {:?}", pattern, text );
+
+                let mut reversed: Vec<_> = text.split_regex(&regex).rev().collect();
+                reversed.reverse();
+                assert_eq!( reversed, expected, "{:?} on {:?}", pattern, text );
+
+                assert_resumes!(text.into_split_regex(&regex));
             }
-
-            None
         }
     }
 
-    impl<'r, S, F, I: 'r> Iterator for IntoSplitRegexRefAndThen<'r, S, F, I>
-    where
-        S: IntoSplitIter,
-        F: FnMut(&str) -> I,
-        I: IntoIterator, {
-
-        type Item = I::Item;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            while let Some(rest) = self.rest.as_mut() {
-                if let Some(result) = rest.next() {
-                    return Some(result);
-                }
-
-                self.rest = self.inner
-                                .next_map(&mut self.fun)
-                                .map(I::into_iter);
-            }
+    #[test]
+    fn bytes_split_end() {
+        use crate::{IterSplit, SplitEnd};
+
+        let comma = re::BytesRegex::new(",").unwrap();
+        let (first, rest) = IterSplit::new((&b"a,\xff,c"[..]).split_regex(&comma))
+            .split_last().unwrap();
+        assert_eq!( first, b"c" );
+        assert_eq!( rest.split_first_n(1).1.collect::<Vec<_>>(), vec![b"\xff"] );
+        assert_eq!( first.split_first(), Some((&b'c', &b""[..])) );
+    }
 
-            None
+    #[test]
+    fn regex_send() {
+        lazy_static! {
+            static ref COMMA: re::Regex = re::Regex::new(" *, *").unwrap();
         }
-    }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use std::io::{Read, BufRead, BufReader};
-        use lazy_static::lazy_static;
+        let s: std::sync::Arc<str> = "a , b,c".into();
+        let iter = s.clone().into_split_regex(COMMA.clone());
+        let pieces = std::thread::spawn(move || iter.collect::<Vec<_>>());
+        let iter = s.into_split_regex_map(&*COMMA, str::len);
+        let lengths = std::thread::spawn(move || iter.collect::<Vec<_>>());
 
-        #[test]
-        fn three_words() {
-            assert_words(
-                "one two three",
-                &["one", "two", "three"]
-            );
-        }
+        assert_eq!( pieces.join().unwrap(), ownv(&["a", "b", "c"]) );
+        assert_eq!( lengths.join().unwrap(), vec![1, 1, 1] );
+    }
 
-        #[test]
-        fn with_punctuation() {
-            assert_words(
-                "one--two-two /three",
-                &["one", "two-two", "three"]
-            );
-        }
+    fn assert_words(input: &str, expected: &[&str]) {
+        assert_eq!( words(input.as_bytes()).collect::<Vec<_>>(),
+                    ownv(expected) );
+    }
 
-        #[test]
-        fn multiple_lines() {
-            assert_words(
-                concat![
-                "first line\n",
-                "\n",
-                "above line was blank!\n",
-            ],
-                &["first", "line", "above", "line", "was", "blank!"]
-            );
-        }
 
-        fn assert_words(input: &str, expected: &[&str]) {
-            assert_eq!( words(input.as_bytes()).collect::<Vec<_>>(),
-                        ownv(expected) );
+    fn words(reader: impl Read) -> impl Iterator<Item = String> {
+        lazy_static! {
+            static ref RE: re::Regex =
+                re::Regex::new("(?:--|/|[[:space:]])+").unwrap();
         }
 
+        BufReader::new(reader).lines()
+            .flat_map(|s| s.unwrap()
+                .into_split_regex_map(&*RE, trim_and_lowercase))
+    }
 
-        fn words(reader: impl Read) -> impl Iterator<Item = String> {
-            lazy_static! {
-                static ref RE: re::Regex =
-                    re::Regex::new("(?:--|/|[[:space:]])+").unwrap();
-            }
+    fn trim_and_lowercase(word: &str) -> String {
+        word.trim().to_lowercase()
+    }
+}
 
-            BufReader::new(reader).lines()
-                .flat_map(|s| s.unwrap()
-                    .into_split_regex_ref_map(&RE, trim_and_lowercase))
-        }
+#[cfg(test)]
+fn ownv<'a, T, I>(seq: I) -> Vec<T::Owned>
+where T: ToOwned + ?Sized + 'a,
+      I: IntoIterator<Item = &'a T>, {
 
-        fn trim_and_lowercase(word: &str) -> String {
-            word.trim().to_lowercase()
-        }
-    }
+    seq.into_iter().map(T::to_owned).collect()
 }
 
 #[cfg(test)]
@@ -504,6 +1968,326 @@ mod tests {
                    ownv(&["one", "two", "three"]));
     }
 
+    #[test]
+    fn split_patterns() {
+        let s = || String::from("a,b;c, d");
+        assert_eq!(s().into_split(',').collect::<Vec<_>>(),
+                   ownv(&["a", "b;c", " d"]));
+        assert_eq!(s().into_split(", ").collect::<Vec<_>>(),
+                   ownv(&["a,b;c", "d"]));
+        assert_eq!(s().into_split(&[',', ';'][..]).collect::<Vec<_>>(),
+                   ownv(&["a", "b", "c", " d"]));
+        assert_eq!(s().into_split([',', ';', ' ']).collect::<Vec<_>>(),
+                   ownv(&["a", "b", "c", "", "d"]));
+        assert_eq!(s().into_split(|c: char| !c.is_alphabetic()).count(), 5);
+    }
+
+    #[test]
+    fn split_map_and_then() {
+        let s: Rc<str> = "1-22-333".into();
+        assert_eq!(Rc::clone(&s).into_split_map('-', str::len).collect::<Vec<_>>(),
+                   vec![1, 2, 3]);
+        assert_eq!(s.into_split_and_then('-', |w| vec![w.len(); w.len()])
+                    .collect::<Vec<_>>(),
+                   vec![1, 2, 2, 3, 3, 3]);
+    }
+
+    #[test]
+    fn split_whitespace_and_then() {
+        let s = String::from("ab  c");
+        assert_eq!(s.into_split_whitespace_and_then(|w| w.chars().rev().collect::<Vec<_>>())
+                    .collect::<String>(),
+                   "bac");
+    }
+
+    #[test]
+    fn str_family() {
+        let s = || -> Box<str> { "a-b-c-".into() };
+        assert_eq!(s().into_rsplit('-').collect::<Vec<_>>(),
+                   ownv(&["", "c", "b", "a"]));
+        assert_eq!(s().into_split_terminator('-').collect::<Vec<_>>(),
+                   ownv(&["a", "b", "c"]));
+        assert_eq!(s().into_splitn(2, '-').collect::<Vec<_>>(),
+                   ownv(&["a", "b-c-"]));
+        assert_eq!(s().into_rsplitn(2, "c-").collect::<Vec<_>>(),
+                   ownv(&["", "a-b-"]));
+        assert_eq!(s().into_split_inclusive('-').collect::<Vec<_>>(),
+                   ownv(&["a-", "b-", "c-"]));
+        assert_eq!(s().into_matches(char::is_alphabetic).collect::<String>(),
+                   "abc");
+        assert_eq!(s().into_match_indices('b').collect::<Vec<_>>(),
+                   vec![(2, "b".to_owned())]);
+    }
+
+    #[test]
+    fn str_family_no_pattern() {
+        let s = String::from("one\r\ntwo\n\u{a0}three four");
+        assert_eq!(s.clone().into_lines().collect::<Vec<_>>(),
+                   ownv(&["one", "two", "\u{a0}three four"]));
+        assert_eq!(s.clone().into_split_ascii_whitespace().count(), 4);
+        assert_eq!(s.into_split_whitespace().count(), 4);
+
+        let s: Rc<str> = "€x".into();
+        assert_eq!(Rc::clone(&s).into_char_indices().collect::<Vec<_>>(),
+                   vec![(0, '€'), (3, 'x')]);
+        assert_eq!(s.into_char_indices_map(|(i, _)| i).collect::<Vec<_>>(),
+                   vec![0, 3]);
+    }
+
+    #[test]
+    fn str_family_map_and_then() {
+        let s = String::from("ab\ncd");
+        assert_eq!(s.clone().into_lines_map(str::len).sum::<usize>(), 4);
+        assert_eq!(s.clone().into_match_indices_map('c', |(i, m)| i + m.len())
+                    .collect::<Vec<_>>(),
+                   vec![4]);
+        assert_eq!(s.into_rsplit_and_then('\n', |w| w.chars().collect::<Vec<_>>())
+                    .collect::<String>(),
+                   "cdab");
+    }
+
+    #[derive(Clone)]
+    struct SplitOn(char);
+
+    impl BorrowIter for SplitOn {
+        type Item<'a> = &'a str;
+        type Iter<'a> = std::str::Split<'a, char>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            base.split(self.0)
+        }
+    }
+
+    struct Lines;
+
+    impl BorrowIter for Lines {
+        type Item<'a> = &'a str;
+        type Iter<'a> = std::str::Lines<'a>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            base.lines()
+        }
+    }
+
+    struct CharIndices;
+
+    impl BorrowIter for CharIndices {
+        type Item<'a> = (usize, char);
+        type Iter<'a> = std::str::CharIndices<'a>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            base.char_indices()
+        }
+    }
+
+    #[test]
+    fn iter_with_split() {
+        let s = String::from("a,b,,c");
+        assert_eq!(s.into_iter_with(SplitOn(','), str::to_owned).collect::<Vec<_>>(),
+                   ownv(&["a", "b", "", "c"]));
+    }
+
+    #[test]
+    fn iter_with_lines() {
+        let s: Rc<str> = "one\ntwo\r\n".into();
+        assert_eq!(s.into_iter_with(Lines, |line| line.len()).collect::<Vec<_>>(),
+                   vec![3, 3]);
+    }
+
+    #[test]
+    fn iter_with_char_indices() {
+        let s: Box<str> = "€6".into();
+        assert_eq!(s.into_iter_with(CharIndices, |(i, c)| (i, c)).collect::<Vec<_>>(),
+                   vec![(0, '€'), (3, '6')]);
+    }
+
+    #[test]
+    fn reversed() {
+        let s = String::from(" a b  c ");
+        assert_eq!(s.clone().into_split_whitespace().rev().collect::<Vec<_>>(),
+                   ownv(&["c", "b", "a"]));
+        assert_eq!(s.clone().into_split_map(' ', str::len).rev().collect::<Vec<_>>(),
+                   vec![0, 1, 0, 1, 1, 0]);
+        assert_eq!(s.clone().into_rsplit(' ').rev().collect::<Vec<_>>(),
+                   s.clone().into_split(' ').collect::<Vec<_>>());
+        assert_eq!(s.into_iter_with(CharIndices, |(i, _)| i).next_back(),
+                   Some(7));
+    }
+
+    #[test]
+    fn both_ends() {
+        let mut iter = String::from("1\n2\n3\n4").into_lines();
+        assert_eq!(iter.next_back(), Some("4".to_owned()));
+        assert_eq!(iter.next(), Some("1".to_owned()));
+        assert_eq!(iter.next_back(), Some("3".to_owned()));
+        assert_eq!(iter.next_back(), Some("2".to_owned()));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn lending() {
+        let mut iter = String::from("to be or not to be").into_split_whitespace();
+        let mut hits = 0;
+        while let Some(word) = iter.next_str() {
+            if ["be", "not"].contains(&word) {
+                hits += 1;
+            }
+        }
+        assert_eq!(hits, 3);
+
+        let mut total = 0;
+        String::from("a,bb,ccc").into_split(',').for_each_str(|s| total += s.len());
+        assert_eq!(total, 6);
+
+        let mut iter = String::from("1\n2\nx\n4").into_lines();
+        assert!(iter.try_fold_str(0, |sum, line| line.parse::<i32>().map(|n| sum + n))
+                    .is_err());
+        assert_eq!(iter.next_str(), Some("4"));
+        assert_eq!(iter.try_fold_str(0, |sum, _| Ok::<_, ()>(sum + 1)), Ok(0));
+    }
+
+    #[test]
+    fn remainder_and_into_inner() {
+        let mut iter = String::from("PRIVMSG #rust :hello,  world").into_split(' ');
+        assert_eq!(iter.next_str(), Some("PRIVMSG"));
+        assert_eq!(iter.next_str(), Some("#rust"));
+        assert_eq!(iter.offset(), 14);
+        assert_eq!(iter.remainder(), ":hello,  world");
+        assert_eq!(iter.into_inner(), "PRIVMSG #rust :hello,  world");
+
+        let mut iter = String::from("a.b.c").into_rsplit_map('.', str::len);
+        iter.next();
+        assert_eq!((iter.remainder(), iter.offset()), ("a.b", 2));
+
+        let mut iter = String::from("one\r\ntwo\nthree").into_lines();
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.remainder(), "two");
+
+        let mut iter = String::from(" x  y z ").into_split_whitespace();
+        iter.next();
+        assert_eq!(iter.remainder(), "y z ");
+
+        let mut iter: IntoCharIndicesAndThen<_, _, Vec<char>> =
+            Rc::<str>::from("€uro").into_char_indices_and_then(|(_, c)| vec![c, c]);
+        iter.next();
+        assert_eq!(iter.remainder(), "uro");
+        assert_eq!(&*iter.into_inner(), "€uro");
+    }
+
+    #[test]
+    fn offset_starts_remainder() {
+        let s = "a::b::c";
+        let mut owned = s.to_owned().into_split("::");
+        while owned.next().is_some() {
+            assert_eq!(&s[owned.offset()..], owned.remainder());
+        }
+        assert_eq!(owned.offset(), s.len());
+
+        let s = " x  y\nz ";
+        let mut owned = s.to_owned().into_split_whitespace();
+        while owned.next().is_some() {
+            assert_eq!(&s[owned.offset()..], owned.remainder());
+        }
+
+        let mut owned = s.to_owned().into_lines();
+        while owned.next().is_some() {
+            assert_eq!(&s[owned.offset()..], owned.remainder());
+        }
+
+        let s = "a.b.c";
+        let mut owned = s.to_owned().into_rsplit('.');
+        while owned.next().is_some() {
+            assert_eq!(&s[.. s.len() - owned.offset()], owned.remainder());
+        }
+    }
+
+    #[test]
+    fn resume_str_family() {
+        for text in &["", ",", ",,", "a", "a,b", ",a,,b,", "a\r\n\nb\n", " x  y ", "é,€"] {
+            let text = *text;
+            assert_resumes!(text.into_split(','), double_ended);
+            assert_resumes!(text.into_split(",,"));
+            assert_resumes!(text.into_split(""));
+            assert_resumes!(text.into_rsplit(','), double_ended);
+            assert_resumes!(text.into_split_terminator(','), double_ended);
+            assert_resumes!(text.into_splitn(3, ','));
+            assert_resumes!(text.into_rsplitn(2, ','));
+            assert_resumes!(text.into_split_inclusive(','), double_ended);
+            assert_resumes!(text.into_matches(""));
+            assert_resumes!(text.into_rsplit(""));
+            assert_resumes!(text.into_matches(','), double_ended);
+            assert_resumes!(text.into_match_indices(','), double_ended);
+            assert_resumes!(text.into_match_indices(""));
+            assert_resumes!(text.into_lines(), double_ended);
+            assert_resumes!(text.into_split_whitespace(), double_ended);
+            assert_resumes!(text.into_split_ascii_whitespace(), double_ended);
+            assert_resumes!(text.into_char_indices(), double_ended);
+            assert_resumes!(text.into_split_map(',', str::len), double_ended);
+        }
+    }
+
+    #[test]
+    fn resume_from_saved() {
+        let mut iter = String::from("alpha beta gamma").into_split(' ');
+        iter.next();
+        let saved = iter.checkpoint();
+        assert_eq!(saved.front, 5);
+        drop(iter);
+
+        let owner = String::from("alpha beta gamma");
+        let rest = IntoSplitMap::resume_from(owner, ' ', str::len, saved);
+        assert_eq!(rest.collect::<Vec<_>>(), vec![4, 5]);
+
+        let mut iter = String::from("a-b-c").into_split_and_then('-', |s| vec![s.to_owned(); 2]);
+        let start = iter.checkpoint();
+        assert_eq!(iter.next(), Some("a".to_owned()));
+        iter.rewind(start);
+        assert_eq!(iter.collect::<String>(), "aabbcc");
+    }
+
+    #[test]
+    fn clone_midway() {
+        let s: Rc<str> = "a b c d".into();
+        let mut iter = Rc::clone(&s).into_split_whitespace();
+        iter.next();
+        let fork = iter.clone();
+        assert_eq!(Rc::strong_count(&s), 3);
+        assert_eq!(iter.collect::<Vec<_>>(), ownv(&["b", "c", "d"]));
+        assert_eq!(fork.rev().collect::<Vec<_>>(), ownv(&["d", "c", "b"]));
+
+        let mut iter = "x=1;y=22".into_split_map(';', |kv| kv.len());
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.clone().next(), Some(4));
+        assert_eq!(iter.next(), Some(4));
+
+        let mut iter = String::from("ab cd").into_split_and_then(' ', |w| w.chars().collect::<Vec<_>>());
+        assert_eq!(iter.next(), Some('a'));
+        assert_eq!(iter.clone().collect::<String>(), "bcd");
+        assert_eq!(iter.collect::<String>(), "bcd");
+
+        let mut iter = Rc::<str>::from("p,q").into_iter_with(SplitOn(','), str::len);
+        iter.next();
+        assert_eq!(iter.clone().chain(iter).collect::<Vec<_>>(), vec![1, 1]);
+    }
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) { }
+
+    #[test]
+    fn send_and_sync() {
+        let s: std::sync::Arc<str> = "a b".into();
+        assert_send_sync(&s.clone().into_split_whitespace());
+        assert_send_sync(&s.clone().into_split_map(' ', str::len));
+        assert_send_sync(&s.clone().into_rsplit_and_then(' ', |w| vec![w.len()]));
+        assert_send_sync(&String::from("a b").into_split(|c: char| c == ' '));
+        assert_send_sync(&"a b".into_iter_with(SplitOn(' '), str::len));
+
+        let iter = s.into_lines();
+        let lines = std::thread::spawn(move || iter.collect::<Vec<_>>())
+            .join().unwrap();
+        assert_eq!(lines, ownv(&["a b"]));
+    }
+
     #[test]
     fn split_borrowed_str() {
         let s = String::from("one two three");
@@ -511,12 +2295,3 @@ mod tests {
                    ownv(&["one", "two", "three"]));
     }
 }
-
-#[cfg(test)]
-fn ownv<'a, T, I>(seq: I) -> Vec<T::Owned>
-where T: ToOwned + ?Sized + 'a,
-      I: IntoIterator<Item = &'a T>, {
-
-    seq.into_iter().map(T::to_owned).collect()
-}
-
@@ -0,0 +1,294 @@
+//! A lexer driven by a regex set.
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
+use regex_automata::meta::{self, BuildError};
+use regex_automata::{Anchored, Input, MatchKind, PatternSet};
+
+use super::re;
+
+/// Splits a string into tokens, each matched by one of an ordered list of
+/// rules. At each position the rule with the longest match wins, and of
+/// those that match the same length, the first. (Within a rule, the regex
+/// picks its match as usual, preferring earlier alternatives.) Input that
+/// no rule matches comes out as an error token whose `kind` is `None`.
+///
+/// Each rule is a token kind and a regex pattern, which matches only at
+/// the start of a token, though anchors and word boundaries see the text
+/// around it; a rule never matches the empty string. Cloning a
+/// `Lexer` is cheap, since the compiled rules are shared.
+///
+/// ```
+/// use split_ext::{Lexer, Token};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Kind { Num, Ident, Op, Eq, Space }
+///
+/// let lexer = Lexer::new(vec![
+///     (Kind::Num,   r"\d+"),
+///     (Kind::Ident, r"\w+"),
+///     (Kind::Op,    r"[-+*/=]"),
+///     (Kind::Eq,    r"=="),
+///     (Kind::Space, r"\s+"),
+/// ]).unwrap();
+///
+/// let kinds: Vec<_> = lexer.tokens("x == 12 $").map(|t| t.kind).collect();
+/// assert_eq!( kinds, [Some(Kind::Ident), Some(Kind::Space), Some(Kind::Eq),
+///                     Some(Kind::Space), Some(Kind::Num), Some(Kind::Space), None] );
+///
+/// let last = lexer.tokens("x == 12 $").last().unwrap();
+/// assert_eq!( last, Token { kind: None, text: "$", span: 8 .. 9 } );
+/// ```
+pub struct Lexer<K> {
+    rules: Arc<Rules<K>>,
+}
+
+struct Rules<K> {
+    // Finds every rule that matches at a position.
+    set:      meta::Regex,
+    regexes:  Vec<meta::Regex>,
+    patterns: Vec<Box<str>>,
+    kinds:    Vec<K>,
+}
+
+impl<K> Lexer<K> {
+    /// Compiles the rules, returning the error from the first invalid
+    /// pattern.
+    pub fn new<I, P>(rules: I) -> Result<Self, re::Error>
+    where
+        I: IntoIterator<Item = (K, P)>,
+        P: AsRef<str>, {
+
+        let mut regexes = Vec::new();
+        let mut patterns = Vec::new();
+        let mut kinds = Vec::new();
+        for (kind, pattern) in rules {
+            let pattern = pattern.as_ref();
+            regexes.push(meta::Regex::new(pattern).map_err(build_error)?);
+            patterns.push(pattern.into());
+            kinds.push(kind);
+        }
+
+        let set = meta::Regex::builder()
+            .configure(meta::Config::new().match_kind(MatchKind::All))
+            .build_many(&patterns)
+            .map_err(build_error)?;
+        Ok(Lexer { rules: Arc::new(Rules { set, regexes, patterns, kinds }) })
+    }
+
+    /// Lexes `text`.
+    pub fn tokens<'h>(&self, text: &'h str) -> Tokens<'h, K> {
+        Tokens::within(self, text, 0, text.len())
+    }
+
+    // The rule and length of the token at `text[start .. back]`, if any.
+    // The rest of `text` is context for anchors and word boundaries.
+    fn longest(&self, text: &str, start: usize, back: usize) -> Option<(usize, usize)> {
+        let rules = &*self.rules;
+        let input = Input::new(text).range(start .. back).anchored(Anchored::Yes);
+        let mut matched = PatternSet::new(rules.set.pattern_len());
+        rules.set.which_overlapping_matches(&input, &mut matched);
+
+        matched.iter()
+            .filter_map(|i| {
+                let i = i.as_usize();
+                Some((i, rules.regexes[i].search(&input)?.len()))
+            })
+            .filter(|&(_, len)| len > 0)
+            .max_by_key(|&(i, len)| (len, Reverse(i)))
+    }
+}
+
+// Reports an error as `regex::Regex::new` would.
+fn build_error(error: BuildError) -> re::Error {
+    match error.size_limit() {
+        Some(limit) => re::Error::CompiledTooBig(limit),
+        None => re::Error::Syntax(match error.syntax_error() {
+            Some(error) => error.to_string(),
+            None => error.to_string(),
+        }),
+    }
+}
+
+// Shares the rules, so the kinds needn’t be `Clone`.
+impl<K> Clone for Lexer<K> {
+    fn clone(&self) -> Self {
+        Lexer { rules: Arc::clone(&self.rules) }
+    }
+}
+
+impl<K: fmt::Debug> fmt::Debug for Lexer<K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rules = &*self.rules;
+        f.debug_map()
+         .entries(rules.kinds.iter().zip(rules.patterns.iter()))
+         .finish()
+    }
+}
+
+/// A token from a [`Lexer`]: its kind, or `None` for input that no rule
+/// matches, its text, and where it lies in the string lexed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Token<K, T = String> {
+    pub kind: Option<K>,
+    pub text: T,
+    pub span: Range<usize>,
+}
+
+impl<K, T> Token<K, T> {
+    /// Whether no rule matched the token.
+    pub fn is_error(&self) -> bool {
+        self.kind.is_none()
+    }
+}
+
+impl<K> Token<K, &str> {
+    /// Copies the text into a `String`.
+    pub fn into_owned(self) -> Token<K> {
+        Token {
+            kind: self.kind,
+            text: self.text.to_owned(),
+            span: self.span,
+        }
+    }
+}
+
+/// The tokens of a string; returned by [`Lexer::tokens`].
+#[derive(Clone, Debug)]
+pub struct Tokens<'h, K> {
+    lexer: Lexer<K>,
+    text:  &'h str,
+    pos:   usize,
+    // Where lexing stops.
+    back:  usize,
+}
+
+impl<'h, K> Tokens<'h, K> {
+    /// Lexes `text[front .. back]`, giving spans in the whole of `text`.
+    /// The rest of `text` is context for anchors and word boundaries.
+    pub fn within(lexer: &Lexer<K>, text: &'h str, front: usize, back: usize)
+                  -> Self {
+        Tokens {
+            lexer: lexer.clone(),
+            text,
+            pos:   front,
+            back,
+        }
+    }
+
+    /// Where the next token begins.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'h, K: Clone> Iterator for Tokens<'h, K> {
+    type Item = Token<K, &'h str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, back) = (self.pos, self.back);
+        if start == back {
+            return None;
+        }
+
+        let lexer = &self.lexer;
+        let text = self.text;
+        let (kind, end) = match lexer.longest(text, start, back) {
+            Some((i, len)) => (Some(lexer.rules.kinds[i].clone()), start + len),
+            // The error runs up to where some rule matches again.
+            None => {
+                let end = text[start .. back].char_indices()
+                    .skip(1)
+                    .map(|(i, _)| start + i)
+                    .find(|&at| lexer.longest(text, at, back).is_some())
+                    .unwrap_or(back);
+                (None, end)
+            }
+        };
+
+        self.pos = end;
+        Some(Token {
+            kind,
+            text: &text[start .. end],
+            span: start .. end,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Kind { Kw, Ident, Num, Space }
+
+    fn lexer() -> Lexer<Kind> {
+        Lexer::new(vec![
+            (Kind::Kw,    "if|else"),
+            (Kind::Ident, r"\w+"),
+            (Kind::Num,   r"\d+(\.\d+)?"),
+            (Kind::Space, r"\s+"),
+        ]).unwrap()
+    }
+
+    fn lex(text: &str) -> Vec<(Option<Kind>, &str)> {
+        lexer().tokens(text).map(|t| (t.kind, t.text)).collect()
+    }
+
+    #[test]
+    fn longest_then_first() {
+        use self::Kind::*;
+        assert_eq!( lex("if iffy 12 1.5"),
+                    [(Some(Kw), "if"), (Some(Space), " "), (Some(Ident), "iffy"),
+                     (Some(Space), " "), (Some(Ident), "12"), (Some(Space), " "),
+                     (Some(Num), "1.5")] );
+        assert_eq!( lex(""), [] );
+    }
+
+    #[test]
+    fn unmatched_input() {
+        use self::Kind::*;
+        assert_eq!( lex("a+-€b"), [(Some(Ident), "a"), (None, "+-€"), (Some(Ident), "b")] );
+        assert_eq!( lex("!?"), [(None, "!?")] );
+
+        let tokens: Vec<_> = lexer().tokens("x €").collect();
+        assert!( tokens[2].is_error() );
+        assert_eq!( tokens[2].span, 2 .. 5 );
+    }
+
+    #[test]
+    fn within() {
+        let lexer = lexer();
+        let mut tokens = Tokens::within(&lexer, "if x else", 3, 6);
+        assert_eq!( tokens.next().map(Token::into_owned),
+                    Some(Token { kind: Some(Kind::Ident), text: "x".to_owned(), span: 3 .. 4 }) );
+        assert_eq!( tokens.position(), 4 );
+        assert_eq!( tokens.map(|t| t.text).collect::<Vec<_>>(), [" ", "e"] );
+    }
+
+    #[test]
+    fn empty_and_invalid_rules() {
+        let lexer = Lexer::new(vec![(0, "a*"), (1, "(?x) b+ # bees")]).unwrap();
+        assert_eq!( lexer.tokens("bca").map(|t| t.kind).collect::<Vec<_>>(),
+                    [Some(1), None, Some(0)] );
+        assert!( Lexer::new(vec![(0, "(")]).is_err() );
+    }
+
+    #[test]
+    fn context_outside_token() {
+        let comment = Lexer::new(vec![(0, "(?m)^#.*"), (1, "[a-z ]")]).unwrap();
+        assert_eq!( comment.tokens("a #b").map(|t| t.kind).collect::<Vec<_>>(),
+                    [Some(1), Some(1), None, Some(1)] );
+        assert_eq!( comment.tokens("a\n#b").map(|t| t.text).collect::<Vec<_>>(),
+                    ["a", "\n", "#b"] );
+
+        let word = Lexer::new(vec![(0, r"\b[a-z]+"), (1, "[0-9]+")]).unwrap();
+        assert_eq!( word.tokens("12ab").map(|t| (t.kind, t.text)).collect::<Vec<_>>(),
+                    [(Some(1), "12"), (None, "ab")] );
+        assert_eq!( word.tokens("12 ab").map(|t| t.kind).collect::<Vec<_>>(),
+                    [Some(1), None, Some(0)] );
+    }
+}
@@ -5,11 +5,26 @@
 //! an iterator that borrows from the string to split it.
 //!
 //! This is an initial, work-in-progress release.
+//!
+//! Without the default `std` feature the crate is `no_std`: `SplitEnd`
+//! remains available for borrowed slices, strings and `Chars`, and the
+//! `alloc` feature adds the impls that need to allocate (such as
+//! `IterSplit`).
+//!
+//! The `rayon` feature adds `ParSplit`, which splits a `&str` or an
+//! `Arc<str>` on several threads at once.
+//!
+//! Regex splitting works with any of three backends, each its own
+//! feature: `regex` (the default), `regex-lite` for smaller binaries, and
+//! `fancy-regex` for lookaround and backreferences. Each backend’s regex
+//! type implements `SplitPattern`, so `RegexSplit` and the owning
+//! `into_split_regex` methods accept it. The rest of the regex support,
+//! such as capture groups and `Lexer`, needs `regex`.
 
-// Still needed on 2018 edition for rental crate:
-#[cfg(feature = "into")]
-#[macro_use]
-extern crate rental;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 #[macro_use]
 mod internal_macros;
@@ -19,20 +34,43 @@ mod utf8;
 mod split_end;
 pub use split_end::*;
 
+#[cfg(any(feature = "into", feature = "split_pattern"))]
+mod haystack;
+#[cfg(any(feature = "into", feature = "split_pattern"))]
+pub use haystack::Haystack;
+
+#[cfg(feature = "into")]
+mod self_ref;
+
+#[cfg(feature = "into")]
+mod pattern;
+#[cfg(feature = "into")]
+pub use pattern::StrPattern;
+
 #[cfg(feature = "into")]
 mod into_split;
 #[cfg(feature = "into")]
 pub use into_split::*;
 
+#[cfg(feature = "split_pattern")]
+mod split_pattern;
+#[cfg(feature = "split_pattern")]
+pub use split_pattern::{IntoRegex, SplitKeep, SplitPattern, SplitRegexEnd, SplitRegexKeep};
+#[cfg(feature = "split_pattern")]
+pub use split_pattern::Split as RegexSplit;
+
 #[cfg(feature = "regex")]
 mod re;
 #[cfg(feature = "regex")]
-pub use re::IntoRegex;
-
-// Dummy definitions for when `regex` is turned off.
-#[cfg(not(feature = "regex"))]
-mod re {
-    pub type Regex = ();
-    pub type Split<'r, 'b> = (&'r (), &'b ());
-}
+pub use re::{BytesSplit, OwnedCaptures, SplitRegexBytes};
+
+#[cfg(feature = "regex")]
+mod lex;
+#[cfg(feature = "regex")]
+pub use lex::{Lexer, Token, Tokens};
+
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "rayon")]
+pub use par::*;
 
@@ -0,0 +1,320 @@
+//! Splitting in parallel with `rayon`.
+
+use std::cmp;
+#[cfg(feature = "regex")]
+use std::convert::Infallible;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::iter::ParallelIterator;
+use rayon::slice::ParallelSlice;
+
+#[cfg(feature = "regex")]
+use rayon::iter::IntoParallelIterator;
+#[cfg(feature = "regex")]
+use super::re;
+#[cfg(feature = "regex")]
+use super::split_pattern::{self, Cursor, SplitPattern};
+
+// The smallest chunk worth handing to another thread.
+const MIN_CHUNK: usize = 1 << 14;
+
+/// Trait for parallel splitting methods. These methods work on `&str`,
+/// whose pieces are borrowed, and `Arc<str>`, whose pieces are `String`s.
+///
+/// The pieces come out in the same order as from the sequential
+/// splitters, so `collect` gives the same result as `split_whitespace`
+/// or `Regex::split`.
+///
+/// Rayon’s `ParallelString` also has a `par_split_whitespace` for `str`,
+/// so importing both traits makes calls on `&str` ambiguous.
+///
+/// ```
+/// use rayon::iter::ParallelIterator;
+/// use split_ext::ParSplit;
+///
+/// let text = "one two\tthree\nfour ".repeat(1000);
+/// let words: Vec<&str> = text.as_str().par_split_whitespace().collect();
+/// assert_eq!( words, text.split_whitespace().collect::<Vec<_>>() );
+/// ```
+pub trait ParSplit: Deref<Target = str> + Clone + Send + Sync {
+    type Piece: Send;
+
+    /// Returns the piece at the byte range `start .. end`.
+    fn piece(&self, start: usize, end: usize) -> Self::Piece;
+
+    /// Splits on whitespace, dividing the text among threads at
+    /// whitespace characters.
+    fn par_split_whitespace(self) -> ParSplitWhitespace<Self> {
+        ParSplitWhitespace { text: self }
+    }
+
+    /// Splits on matches of `regex`. The text is divided among threads,
+    /// each of which searches its own part. Since where a regex matches
+    /// can depend on where the search began, a match that straddles two
+    /// parts means searching again after it until the matches agree with
+    /// the next part’s. The pieces are then made in parallel too.
+    ///
+    /// A regex that seldom matches leaves each thread searching well past
+    /// its own part, so it is no faster than splitting sequentially.
+    #[cfg(feature = "regex")]
+    fn par_split_regex(self, regex: &re::Regex) -> ParSplitRegex<Self> {
+        // One part per thread, since a part may be searched well past
+        // its end.
+        let chunk = self.len() / rayon::current_num_threads();
+        let spans = regex_spans(regex, &self, cmp::max(chunk, MIN_CHUNK));
+        ParSplitRegex { text: self, spans }
+    }
+}
+
+impl<'a> ParSplit for &'a str {
+    type Piece = &'a str;
+
+    fn piece(&self, start: usize, end: usize) -> Self::Piece {
+        let text: &'a str = self;
+        &text[start .. end]
+    }
+}
+
+impl ParSplit for Arc<str> {
+    type Piece = String;
+
+    fn piece(&self, start: usize, end: usize) -> Self::Piece {
+        self[start .. end].to_owned()
+    }
+}
+
+/// The parallel iterator returned by `ParSplit::par_split_whitespace`.
+#[derive(Clone, Debug)]
+pub struct ParSplitWhitespace<S> {
+    text: S,
+}
+
+impl<S: ParSplit> ParallelIterator for ParSplitWhitespace<S> {
+    type Item = S::Piece;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item> {
+
+        let chunk = self.text.len() / (rayon::current_num_threads() * 4);
+        let cuts = whitespace_cuts(&self.text, cmp::max(chunk, MIN_CHUNK));
+        let text = self.text;
+
+        cuts.par_windows(2)
+            .flat_map_iter(|cut| {
+                text[cut[0] .. cut[1]].split_whitespace()
+                    .map(|word| {
+                        let start = word.as_ptr() as usize - text.as_ptr() as usize;
+                        text.piece(start, start + word.len())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+// Divides `text` into chunks of at least `chunk` bytes, each ending just
+// before a whitespace character (or at the end), so that no word
+// straddles two chunks.
+fn whitespace_cuts(text: &str, chunk: usize) -> Vec<usize> {
+    let mut cuts = vec![0];
+    let mut target = chunk;
+
+    while target < text.len() {
+        while !text.is_char_boundary(target) {
+            target += 1;
+        }
+
+        match text[target ..].find(char::is_whitespace) {
+            Some(i) => {
+                cuts.push(target + i);
+                target += i + chunk;
+            }
+            None => break,
+        }
+    }
+
+    cuts.push(text.len());
+    cuts
+}
+
+// Finds the pieces that splitting `text` on `regex` gives, as byte
+// ranges. Parts of at least `chunk` bytes are searched in parallel, and
+// then their matches are reconciled in order.
+#[cfg(feature = "regex")]
+fn regex_spans<R>(regex: &R, text: &str, chunk: usize) -> Vec<(usize, usize)>
+where
+    R: SplitPattern<Text = str, Error = Infallible> + Sync, {
+
+    let cuts = char_cuts(text, chunk);
+    // Where the matches of each part start before; the last part also
+    // takes an empty match at the very end.
+    let limit = |cut: usize| if cut == text.len() { usize::MAX } else { cut };
+
+    // Each part’s matches as if a search had started at its beginning.
+    let found: Vec<Vec<(usize, usize)>> = cuts.par_windows(2)
+        .map(|cut| {
+            let mut cursor = Cursor::new(cut[0], text.len());
+            let find = |at| split_pattern::find_infallible(regex, text, at);
+            std::iter::from_fn(|| cursor.next(text, find, |&m| m))
+                .take_while(|&(start, _)| start < limit(cut[1]))
+                .collect()
+        })
+        .collect();
+
+    let mut cursor = Cursor::new(0, text.len());
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut take = |(delim_start, delim_end): (usize, usize)| {
+        spans.push((start, delim_start));
+        start = delim_end;
+    };
+
+    'parts: for (cut, found) in cuts.windows(2).zip(found) {
+        while cursor.search < limit(cut[1]) {
+            // The sequential search agrees with this part’s from the first
+            // of its matches that starts at or after where it resumes, as
+            // long as the one before that ended by then. An empty match
+            // right where the last one ended is the exception, since the
+            // sequential search skips it.
+            let i = found.partition_point(|&(start, _)| start < cursor.search);
+            let in_step = i == 0 || found[i - 1].1 <= cursor.search;
+            if in_step && i == found.len() {
+                break;
+            }
+            if in_step && !cursor.skips(found[i]) {
+                found[i ..].iter().copied().for_each(&mut take);
+                cursor.resume_after(found[found.len() - 1].1);
+                break;
+            }
+
+            let find = |at| split_pattern::find_infallible(regex, text, at);
+            match cursor.next(text, find, |&m| m) {
+                Some(delim) => take(delim),
+                None => break 'parts,
+            }
+        }
+    }
+
+    spans.push((start, text.len()));
+    spans
+}
+
+// Divides `text` into chunks of at least `chunk` bytes at `char`
+// boundaries.
+#[cfg(feature = "regex")]
+fn char_cuts(text: &str, chunk: usize) -> Vec<usize> {
+    let mut cuts = vec![0];
+    let mut target = chunk;
+
+    while target < text.len() {
+        while !text.is_char_boundary(target) {
+            target += 1;
+        }
+        cuts.push(target);
+        target += chunk;
+    }
+
+    cuts.push(text.len());
+    cuts
+}
+
+/// The parallel iterator returned by `ParSplit::par_split_regex`.
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+pub struct ParSplitRegex<S> {
+    text:  S,
+    spans: Vec<(usize, usize)>,
+}
+
+#[cfg(feature = "regex")]
+impl<S: ParSplit> ParallelIterator for ParSplitRegex<S> {
+    type Item = S::Piece;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item> {
+
+        let text = self.text;
+        self.spans.into_par_iter()
+            .map(|(start, end)| text.piece(start, end))
+            .drive_unindexed(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.spans.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTS: &[&str] = &[
+        "", " ", "a", " a ", "one two  three", "\u{a0}é\u{3000}ü\tx\n\ny ",
+    ];
+
+    #[test]
+    fn cuts_at_whitespace() {
+        for text in TEXTS {
+            for chunk in 1 .. 6 {
+                let cuts = whitespace_cuts(text, chunk);
+                let words: Vec<_> = cuts.windows(2)
+                    .flat_map(|cut| text[cut[0] .. cut[1]].split_whitespace())
+                    .collect();
+                assert_eq!( words, text.split_whitespace().collect::<Vec<_>>(),
+                            "{:?} by {}", text, chunk );
+            }
+        }
+    }
+
+    #[test]
+    fn same_as_sequential() {
+        let text: Arc<str> = "lorem ipsum\u{2003}dolor\n\n sit\tamet ".repeat(5000).into();
+        let expected: Vec<_> = text.split_whitespace().collect();
+
+        assert_eq!( (&*text).par_split_whitespace().collect::<Vec<_>>(), expected );
+        assert_eq!( Arc::clone(&text).par_split_whitespace().collect::<Vec<_>>(),
+                    expected );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_spans_reconcile() {
+        let cases = [
+            (" *, *|^|$", "a, b,,c ,d"),
+            ("aba", "abababa ababa"),
+            ("a+", "aaa baaaa a"),
+            ("", "aé€b"),
+            ("x*", "xxaxxé"),
+            (r"\b", "one two"),
+            ("(?m)^|;", "a;b\n;c\n"),
+        ];
+
+        for &(pattern, text) in &cases {
+            let regex = re::Regex::new(pattern).unwrap();
+            let expected: Vec<_> = regex.split(text).collect();
+            for chunk in 1 .. 6 {
+                let pieces: Vec<_> = regex_spans(&regex, text, chunk).into_iter()
+                    .map(|(start, end)| &text[start .. end])
+                    .collect();
+                assert_eq!( pieces, expected, "{:?} in {:?} by {}", pattern, text, chunk );
+            }
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_same_as_sequential() {
+        let text: Arc<str> = "a, b,,c ,d".repeat(5000).into();
+        let regex = re::Regex::new(" *, *|^|$").unwrap();
+        let expected: Vec<_> = regex.split(&text).collect();
+
+        let pieces = Arc::clone(&text).par_split_regex(&regex);
+        assert_eq!( pieces.opt_len(), Some(expected.len()) );
+        assert_eq!( pieces.collect::<Vec<_>>(), expected );
+        assert_eq!( (&*text).par_split_regex(&regex).collect::<Vec<_>>(), expected );
+    }
+}
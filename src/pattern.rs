@@ -0,0 +1,93 @@
+use std::str;
+
+/// The delimiters that [`IntoSplitIter::into_split`] and its relatives
+/// accept. These are the same as for `str::split`, except that closures
+/// must be `Clone`, since `std`’s own `Pattern` trait cannot be named on
+/// stable Rust.
+///
+/// Each method calls the `str` method of the same name on `haystack`.
+///
+/// [`IntoSplitIter::into_split`]: crate::IntoSplitIter::into_split
+pub trait StrPattern: Clone {
+    type Split<'a>: Iterator<Item = &'a str>;
+    type RSplit<'a>: Iterator<Item = &'a str>;
+    type SplitTerminator<'a>: Iterator<Item = &'a str>;
+    type SplitN<'a>: Iterator<Item = &'a str>;
+    type RSplitN<'a>: Iterator<Item = &'a str>;
+    type SplitInclusive<'a>: Iterator<Item = &'a str>;
+    type Matches<'a>: Iterator<Item = &'a str>;
+    type MatchIndices<'a>: Iterator<Item = (usize, &'a str)>;
+
+    fn split_in(self, haystack: &str) -> Self::Split<'_>;
+
+    fn rsplit_in(self, haystack: &str) -> Self::RSplit<'_>;
+
+    fn split_terminator_in(self, haystack: &str) -> Self::SplitTerminator<'_>;
+
+    fn splitn_in(self, n: usize, haystack: &str) -> Self::SplitN<'_>;
+
+    fn rsplitn_in(self, n: usize, haystack: &str) -> Self::RSplitN<'_>;
+
+    fn split_inclusive_in(self, haystack: &str) -> Self::SplitInclusive<'_>;
+
+    fn matches_in(self, haystack: &str) -> Self::Matches<'_>;
+
+    fn match_indices_in(self, haystack: &str) -> Self::MatchIndices<'_>;
+}
+
+macro_rules! impl_str_pattern {
+    ([$($gen:tt)*] $t:ty $(where $($bound:tt)*)?) => {
+        impl<$($gen)*> StrPattern for $t $(where $($bound)*)? {
+            type Split<'a> = str::Split<'a, $t>;
+            type RSplit<'a> = str::RSplit<'a, $t>;
+            type SplitTerminator<'a> = str::SplitTerminator<'a, $t>;
+            type SplitN<'a> = str::SplitN<'a, $t>;
+            type RSplitN<'a> = str::RSplitN<'a, $t>;
+            type SplitInclusive<'a> = str::SplitInclusive<'a, $t>;
+            type Matches<'a> = str::Matches<'a, $t>;
+            type MatchIndices<'a> = str::MatchIndices<'a, $t>;
+
+            fn split_in(self, haystack: &str) -> Self::Split<'_> {
+                haystack.split(self)
+            }
+
+            fn rsplit_in(self, haystack: &str) -> Self::RSplit<'_> {
+                haystack.rsplit(self)
+            }
+
+            fn split_terminator_in(self, haystack: &str)
+                                   -> Self::SplitTerminator<'_> {
+                haystack.split_terminator(self)
+            }
+
+            fn splitn_in(self, n: usize, haystack: &str) -> Self::SplitN<'_> {
+                haystack.splitn(n, self)
+            }
+
+            fn rsplitn_in(self, n: usize, haystack: &str) -> Self::RSplitN<'_> {
+                haystack.rsplitn(n, self)
+            }
+
+            fn split_inclusive_in(self, haystack: &str)
+                                  -> Self::SplitInclusive<'_> {
+                haystack.split_inclusive(self)
+            }
+
+            fn matches_in(self, haystack: &str) -> Self::Matches<'_> {
+                haystack.matches(self)
+            }
+
+            fn match_indices_in(self, haystack: &str) -> Self::MatchIndices<'_> {
+                haystack.match_indices(self)
+            }
+        }
+    };
+}
+
+impl_str_pattern!([] char);
+impl_str_pattern!(['b] &'b str);
+impl_str_pattern!(['b] &'b String);
+impl_str_pattern!(['b] &'b [char]);
+impl_str_pattern!(['b, const N: usize] &'b [char; N]);
+impl_str_pattern!([const N: usize] [char; N]);
+impl_str_pattern!([F] F where F: FnMut(char) -> bool + Clone);
@@ -1,63 +1,259 @@
 use std::fmt;
-use stable_deref_trait::StableDeref;
+use std::ops::{self, Range};
+use std::sync::Arc;
+
+use crate::split_pattern::{self, Cursor, Split};
 
 pub type Regex = regex::Regex;
+pub type BytesRegex = regex::bytes::Regex;
+pub type Error = regex::Error;
+
+/// Compiles `pattern`, or returns the regex compiled for it earlier if it
+/// is still cached.
+#[cfg_attr(not(feature = "into"), allow(dead_code))]
+pub fn cached(pattern: &str) -> Result<Arc<Regex>, Error> {
+    split_pattern::cached(pattern)
+}
+
+/// Like `regex::CaptureMatches`, but able to start partway through the
+/// text.
+#[cfg_attr(not(feature = "into"), allow(dead_code))]
+pub struct CaptureMatches<'r, 'h> {
+    regex:  &'r Regex,
+    text:   &'h str,
+    cursor: Cursor,
+}
 
-// Wrap `regex::Split` in order to impl `Debug` for it.
-pub struct Split<'r, 'b>(pub regex::Split<'r, 'b>);
+#[cfg_attr(not(feature = "into"), allow(dead_code))]
+impl<'r, 'h> CaptureMatches<'r, 'h> {
+    /// Finds the matches within `text[front .. back]`, with the rest of
+    /// `text` as context.
+    pub fn within(regex: &'r Regex, text: &'h str, front: usize, back: usize)
+                  -> Self {
+        assert!(front <= back && back <= text.len(),
+                "re::CaptureMatches::within: out of bounds");
+        CaptureMatches { regex, text, cursor: Cursor::new(front, back) }
+    }
+}
 
-impl<'r, 'b> Iterator for Split<'r, 'b> {
-    type Item = &'b str;
+impl<'r, 'h> Iterator for CaptureMatches<'r, 'h> {
+    type Item = regex::Captures<'h>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        let (regex, text) = (self.regex, self.text);
+        self.cursor.next(text,
+                         |at| regex.captures_at(text, at),
+                         |caps| caps.get(0).map_or((0, 0), |m| (m.start(), m.end())))
     }
 }
 
-impl<'r, 'b> fmt::Debug for Split<'r, 'b> {
+impl<'r, 'h> fmt::Debug for CaptureMatches<'r, 'h> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "regex::Split(...)")
+        f.debug_struct("CaptureMatches")
+         .field("pattern", &self.regex.as_str())
+         .field("position", &self.cursor.search)
+         .finish()
     }
 }
 
-/// Allows passing either a borrowed or an owned `Regex`
-/// to a method that needs to own a stable pointer to it.
-pub trait IntoRegex: Sized {
-    type StableRegex: StableDeref<Target = regex::Regex>;
+/// The capture groups of one match, like `regex::Captures` but owning a
+/// copy of the matched text, so it can outlive the string searched.
+///
+/// Groups can be had by index or by name, either with `get` and `name` or
+/// by indexing, which panics if the group didn’t take part in the match.
+/// Spans are byte ranges in the whole string searched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnedCaptures {
+    text:  String,
+    spans: Box<[Option<(usize, usize)>]>,
+    names: Arc<[Option<Box<str>>]>,
+}
+
+impl OwnedCaptures {
+    // `names` should come from `capture_names` on the same regex.
+    #[cfg_attr(not(feature = "into"), allow(dead_code))]
+    pub(crate) fn new(caps: &regex::Captures<'_>,
+                      names: &Arc<[Option<Box<str>>]>) -> Self {
+        let whole = caps.get(0).expect("group 0 always matches");
+        OwnedCaptures {
+            text:  whole.as_str().to_owned(),
+            spans: caps.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect(),
+            names: Arc::clone(names),
+        }
+    }
+
+    /// The text of the whole match.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The number of groups, including the whole match as group 0.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Always `false`, since there is always group 0.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The text of group `i`, if it took part in the match.
+    pub fn get(&self, i: usize) -> Option<&str> {
+        let offset = self.spans[0]?.0;
+        let (start, end) = (*self.spans.get(i)?)?;
+        Some(&self.text[start - offset .. end - offset])
+    }
+
+    /// The text of the group called `name`, if it took part in the match.
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.get(self.index_of(name)?)
+    }
 
-    fn into_stable_regex(self) -> Self::StableRegex;
+    /// Where group `i` lies in the string searched.
+    pub fn span(&self, i: usize) -> Option<Range<usize>> {
+        self.spans.get(i).copied().flatten().map(|(start, end)| start .. end)
+    }
+
+    /// Where the group called `name` lies in the string searched.
+    pub fn name_span(&self, name: &str) -> Option<Range<usize>> {
+        self.span(self.index_of(name)?)
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n.as_deref() == Some(name))
+    }
 }
 
-impl IntoRegex for Regex {
-    type StableRegex = Box<Regex>;
+impl ops::Index<usize> for OwnedCaptures {
+    type Output = str;
 
-    fn into_stable_regex(self) -> Self::StableRegex {
-        self.into()
+    fn index(&self, i: usize) -> &str {
+        self.get(i)
+            .unwrap_or_else(|| panic!("no group at index '{}'", i))
     }
 }
 
-macro_rules! impl_into_regex {
-    (impl<$a:lifetime> IntoRegex<$b:lifetime> for $t:ty) => {
-        impl<$a> IntoRegex for $t {
-            type StableRegex = Self;
+impl<'a> ops::Index<&'a str> for OwnedCaptures {
+    type Output = str;
 
-            fn into_stable_regex(self) -> Self::StableRegex {
-                self
-            }
-        }
-    };
+    fn index(&self, name: &'a str) -> &str {
+        self.name(name)
+            .unwrap_or_else(|| panic!("no group named '{}'", name))
+    }
+}
 
-    (<$a:lifetime> $t:ty) => {
-        impl_into_regex!(impl<$a> IntoRegex<$a> for $t);
-    };
+/// Splits a byte string on a `regex::bytes::Regex`; returned by
+/// [`SplitRegexBytes::split_regex`].
+///
+/// Unlike `regex::bytes::Split` it is double-ended, so wrapping it in an
+/// [`IterSplit`](crate::IterSplit) gives the full `SplitEnd` interface.
+#[derive(Debug)]
+pub struct BytesSplit<'r, 'b>(Split<'r, 'b, BytesRegex>);
 
-    ($t:ty) => {
-        impl_into_regex!(impl<'a> IntoRegex<'static> for $t);
-    };
+impl<'r, 'b> BytesSplit<'r, 'b> {
+    /// Where the next piece from the front begins.
+    pub fn position(&self) -> usize {
+        self.0.position()
+    }
+
+    /// The part of the text not yet split off.
+    pub fn remainder(&self) -> &'b [u8] {
+        self.0.remainder()
+    }
+}
+
+impl<'r, 'b> Iterator for BytesSplit<'r, 'b> {
+    type Item = &'b [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<'r, 'b> DoubleEndedIterator for BytesSplit<'r, 'b> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+/// Splits borrowed byte strings, which needn’t be UTF-8, on a
+/// `regex::bytes::Regex`.
+///
+/// ```
+/// use split_ext::{IterSplit, SplitEnd, SplitRegexBytes};
+///
+/// let tab = regex::bytes::Regex::new("\t").unwrap();
+/// let row = &b"caf\xe9\t\xff\tend"[..];
+/// let fields = IterSplit::new(row.split_regex(&tab));
+/// let (last, rest) = fields.split_last().unwrap();
+/// assert_eq!( last, b"end" );
+/// assert_eq!( rest.collect::<Vec<_>>(), vec![&b"caf\xe9"[..], b"\xff"] );
+/// ```
+pub trait SplitRegexBytes<'b> {
+    fn split_regex<'r>(self, regex: &'r BytesRegex) -> BytesSplit<'r, 'b>;
+}
+
+impl<'b> SplitRegexBytes<'b> for &'b [u8] {
+    fn split_regex<'r>(self, regex: &'r BytesRegex) -> BytesSplit<'r, 'b> {
+        BytesSplit(Split::new(regex, self))
+    }
 }
 
-impl_into_regex!(<'a> &'a Regex);
-impl_into_regex!(Box<Regex>);
-impl_into_regex!(std::rc::Rc<Regex>);
-impl_into_regex!(std::sync::Arc<Regex>);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split_pattern::SplitRegexEnd;
+
+    fn re(pattern: &str) -> Regex {
+        Regex::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn split_first_regex() {
+        assert_eq!( "123abc".split_first_regex(&re(r"\d+")), Some(("123", "abc")) );
+        assert_eq!( "abc123".split_first_regex(&re(r"\d+")), None );
+        assert_eq!( "ax".split_first_regex(&re(r"x|\d")), None );
+        assert_eq!( "ab".split_first_regex(&re("a|ab")), Some(("a", "b")) );
+        assert_eq!( "x".split_first_regex(&re(r"\d*")), Some(("", "x")) );
+        assert_eq!( "ABCd".split_first_regex(&re("(?i)abc")), Some(("ABC", "d")) );
+        assert_eq!( "42 x".split_first_regex(&re("(?x) \\d+ # digits")),
+                    Some(("42", " x")) );
+
+        let (word, rest) = "héllo wörld".chars().split_first_regex(&re(r"\w+")).unwrap();
+        assert_eq!( (word.as_str(), rest.as_str()), ("héllo", " wörld") );
+    }
+
+    #[test]
+    fn split_last_regex() {
+        assert_eq!( "abc123".split_last_regex(&re(r"\d+")), Some(("123", "abc")) );
+        assert_eq!( "123abc".split_last_regex(&re(r"\d+")), None );
+        assert_eq!( "aaa".split_last_regex(&re("aa")), Some(("aa", "a")) );
+        assert_eq!( "ab".split_last_regex(&re("a|ab")), None );
+        assert_eq!( "ab".split_last_regex(&re("ab|b")), Some(("ab", "")) );
+        assert_eq!( "x".split_last_regex(&re(r"\d*")), Some(("", "x")) );
 
+        let (word, rest) = "héllo wörld".chars().split_last_regex(&re(r"\w+")).unwrap();
+        assert_eq!( (word.as_str(), rest.as_str()), ("wörld", "héllo ") );
+    }
+
+    #[test]
+    fn split_regex_end_with_builder() {
+        let ci = regex::RegexBuilder::new("abc").case_insensitive(true).build().unwrap();
+        assert_eq!( "ABCd".split_first_regex(&ci), Some(("ABC", "d")) );
+        assert_eq!( "dAbC".split_last_regex(&ci), Some(("AbC", "d")) );
+
+        let long = regex::RegexBuilder::new(r"\w{1000}").size_limit(1 << 30).build().unwrap();
+        let word = "é".repeat(1000);
+        assert_eq!( word.split_first_regex(&long), Some((&word[..], "")) );
+        assert_eq!( word.split_last_regex(&long), Some((&word[..], "")) );
+    }
+
+    #[test]
+    fn split_bytes_regex() {
+        let high = BytesRegex::new(r"(?-u)[\x80-\xff]+").unwrap();
+        let text = &b"\xff\xfe12\x80"[..];
+        assert_eq!( text.split_first_regex(&high), Some((&b"\xff\xfe"[..], &b"12\x80"[..])) );
+        assert_eq!( text.split_last_regex(&high), Some((&b"\x80"[..], &b"\xff\xfe12"[..])) );
+        assert_eq!( (&b"12"[..]).split_first_regex(&high), None );
+    }
+}
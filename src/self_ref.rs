@@ -0,0 +1,746 @@
+//! A small self-referential core: an owned string packaged together with
+//! an iterator that borrows from it. The string may also be a byte string.
+//!
+//! The iterator is stored with its lifetime stretched to `'static`. This is
+//! sound because the string lives behind a `StableDeref` pointer, so moving
+//! the owner does not move the text, and because the iterator is only ever
+//! lent out at a lifetime bounded by a borrow of the `SelfRef` itself.
+
+use std::fmt;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ops::Deref;
+use std::ptr;
+
+use stable_deref_trait::{CloneStableDeref, StableDeref};
+
+use crate::haystack::Haystack;
+
+/// A family of iterators that borrow from a `&str`, for use with
+/// [`IntoSplitIter::into_iter_with`](crate::IntoSplitIter::into_iter_with).
+///
+/// The iterator may borrow from the string but not from `self`, so
+/// any state it needs from `self` has to be copied or cloned into it.
+///
+/// ```
+/// use split_ext::{BorrowIter, IntoSplitIter};
+///
+/// struct Commas;
+///
+/// impl BorrowIter for Commas {
+///     type Item<'a> = &'a str;
+///     type Iter<'a> = std::str::Split<'a, char>;
+///
+///     fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+///         base.split(',')
+///     }
+/// }
+///
+/// let fields = String::from("a,b,,c")
+///     .into_iter_with(Commas, |s| s.len())
+///     .collect::<Vec<_>>();
+/// assert_eq!( fields, vec![1, 1, 0, 1] );
+/// ```
+pub trait BorrowIter {
+    type Item<'a>;
+    type Iter<'a>: Iterator<Item = Self::Item<'a>>;
+
+    fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a>;
+}
+
+/// A way of splitting a borrowed string, which unlike [`BorrowIter`] may
+/// also borrow from `self`.
+///
+/// # Safety
+///
+/// The iterator may borrow from `self` for longer than `&self` only what
+/// `self` reaches through a stable pointer (such as a `Box` or a
+/// reference), never `self`’s own fields. `SelfRef` moves the splitter
+/// but keeps it alive until the iterator is gone.
+pub(crate) unsafe trait Splitter<T: ?Sized = str> {
+    type Item<'a> where T: 'a;
+    type Iter<'a>: Iterator<Item = Self::Item<'a>> where T: 'a;
+
+    fn split<'a>(&self, base: &'a T) -> Self::Iter<'a>;
+}
+
+// The iterator cannot borrow from `self` at all.
+unsafe impl<T: BorrowIter> Splitter for T {
+    type Item<'a> = T::Item<'a>;
+    type Iter<'a> = T::Iter<'a>;
+
+    fn split<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+        self.iter(base)
+    }
+}
+
+/// A splitter whose items are pieces of the text, so that `SelfRef` can
+/// keep track of how much of the text is left.
+pub(crate) trait Pieces<T: ?Sized + Haystack = str>: Splitter<T> {
+    /// Whether `next` takes pieces from the end of the text.
+    const REVERSED: bool = false;
+
+    /// Whether `resume` carries on exactly where the checkpoint was taken,
+    /// so that `SelfRef` needn’t drop empty pieces at either end. Several
+    /// items may then lie at the same place.
+    const EXACT_RESUME: bool = false;
+
+    fn span(base: &T, item: &Self::Item<'_>) -> (usize, usize);
+
+    /// Splits what is left of `base` at `from`. The result may begin with
+    /// an empty piece at `from.front` if pieces were taken from the front,
+    /// and likewise end with one at `from.back`; `SelfRef` drops those.
+    fn resume<'a>(&self, base: &'a T, from: &Checkpoint) -> Self::Iter<'a> {
+        self.split(&base[from.front .. from.back])
+    }
+
+    /// Narrows `from.front .. from.back` to leave out the delimiters next
+    /// to the pieces taken from either end, for splitters that drop their
+    /// delimiters.
+    fn trim(&self, _base: &T, from: &Checkpoint) -> (usize, usize) {
+        (from.front, from.back)
+    }
+}
+
+/// A saved position in an owning splitter, for resuming it later with
+/// `rewind` or `resume_from`.
+///
+/// The pieces not yet returned lie within the byte range `front .. back`
+/// of the string. The fields are public so that a checkpoint can be
+/// stored and rebuilt, but resuming from one that didn’t come from the
+/// same string and splitter gives unspecified pieces, and panics if the
+/// offsets aren’t `char` boundaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// Where the pieces taken from the front end.
+    pub front:       usize,
+    /// Where the pieces taken from the back begin.
+    pub back:        usize,
+    /// How many pieces have been taken from the front.
+    pub taken_front: usize,
+    /// How many pieces have been taken from the back.
+    pub taken_back:  usize,
+    /// Whether there are no pieces left. This can’t be told from the
+    /// other fields when `front == back` and the delimiter may be empty.
+    pub exhausted:   bool,
+}
+
+/// An item that occupies the byte range `span` of the text it came from.
+pub(crate) trait Piece<T: ?Sized = str> {
+    fn span(&self, base: &T) -> (usize, usize);
+}
+
+impl Piece for &str {
+    fn span(&self, base: &str) -> (usize, usize) {
+        let start = self.as_ptr() as usize - base.as_ptr() as usize;
+        (start, start + self.len())
+    }
+}
+
+impl Piece<[u8]> for &[u8] {
+    fn span(&self, base: &[u8]) -> (usize, usize) {
+        let start = self.as_ptr() as usize - base.as_ptr() as usize;
+        (start, start + self.len())
+    }
+}
+
+impl Piece for (usize, &str) {
+    fn span(&self, _base: &str) -> (usize, usize) {
+        (self.0, self.0 + self.1.len())
+    }
+}
+
+impl Piece for (usize, char) {
+    fn span(&self, _base: &str) -> (usize, usize) {
+        (self.0, self.0 + self.1.len_utf8())
+    }
+}
+
+/// Owns a string and a splitter along with the splitter’s live iterator
+/// over the string.
+pub(crate) struct SelfRef<S, P>
+where
+    S: Deref,
+    S::Target: Haystack,
+    P: Splitter<S::Target>, {
+
+    // Declared first so it drops first; see `Drop` below.
+    iter:     ManuallyDrop<P::Iter<'static>>,
+    splitter: Aliasable<P>,
+    base:     Aliasable<S>,
+    // Tracked only for `Pieces`.
+    pos:      Checkpoint,
+    // Whether the iterator may still yield an empty piece at `pos.front`
+    // or `pos.back` that was already taken before `rewind`.
+    stale_front: bool,
+    stale_back:  bool,
+}
+
+impl<S, P> SelfRef<S, P>
+where
+    S: StableDeref,
+    S::Target: Haystack,
+    P: Splitter<S::Target>, {
+
+    pub fn new(base: S, splitter: P) -> Self {
+        let base = Aliasable::new(base);
+        let splitter = Aliasable::new(splitter);
+
+        // SAFETY: The text of `base` stays put when `base` is moved into
+        // the result, and `Drop` ends `iter` before freeing it.
+        let iter = unsafe {
+            let text: &'static S::Target = &*(&**base.get() as *const _);
+            splitter.get().split(text)
+        };
+
+        let pos = Checkpoint {
+            back: base.get().len(),
+            ..Checkpoint::default()
+        };
+
+        SelfRef {
+            iter: ManuallyDrop::new(iter),
+            splitter,
+            base,
+            pos,
+            stale_front: false,
+            stale_back:  false,
+        }
+    }
+
+    /// Gives back the string, dropping the iterator and the splitter.
+    pub fn into_inner(self) -> S {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: Each field is dropped or moved out exactly once, the
+        // iterator first, and `this` is never dropped itself.
+        unsafe {
+            ManuallyDrop::drop(&mut this.iter);
+            ptr::drop_in_place(&mut this.splitter);
+            ptr::read(&this.base).into_inner()
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn splitter(&self) -> &P {
+        self.splitter.get()
+    }
+
+    /// Runs `f` on the iterator at a lifetime that cannot escape `f`.
+    pub fn rent_mut<R, F>(&mut self, f: F) -> R
+    where
+        F: for<'a> FnOnce(&'a mut P::Iter<'a>) -> R {
+
+        let iter: *mut P::Iter<'static> = &mut *self.iter;
+        // SAFETY: Shortening the lifetime is fine because `f` cannot name
+        // it, so it can neither keep the items nor store a different
+        // iterator (except one borrowing only `'static` data).
+        f(unsafe { &mut *iter.cast() })
+    }
+
+    pub fn next_map<R, F>(&mut self, fun: F) -> Option<R>
+    where
+        P: Pieces<S::Target>,
+        F: for<'a> FnOnce(P::Item<'a>) -> R {
+
+        self.next_ref().map(fun)
+    }
+
+    /// Lends out the next item for as long as `self` stays borrowed.
+    pub fn next_ref(&mut self) -> Option<P::Item<'_>>
+    where
+        P: Pieces<S::Target> {
+
+        if self.pos.exhausted {
+            return None;
+        }
+
+        loop {
+            // Taken afresh each time round, since `consume` borrows all of
+            // `self` and so invalidates any earlier pointer.
+            let iter: *mut P::Iter<'static> = &mut *self.iter;
+            // SAFETY: As in `rent_mut`, except that the lifetime is that of
+            // the borrow of `self`, which keeps the text alive and in place.
+            // Only `next` sees the shortened iterator, so nothing gets
+            // stored in it.
+            let item = unsafe { (*iter.cast::<P::Iter<'_>>()).next() };
+            match item {
+                None => {
+                    self.pos.exhausted = true;
+                    return None;
+                }
+                Some(item) => if self.consume(&item, P::REVERSED) {
+                    return Some(item);
+                }
+            }
+        }
+    }
+
+    pub fn next_back_map<R, F>(&mut self, fun: F) -> Option<R>
+    where
+        P: Pieces<S::Target>,
+        F: for<'a> FnOnce(P::Item<'a>) -> R,
+        for<'a> P::Iter<'a>: DoubleEndedIterator, {
+
+        if self.pos.exhausted {
+            return None;
+        }
+
+        loop {
+            let iter: *mut P::Iter<'static> = &mut *self.iter;
+            // SAFETY: As in `next_ref`, which also explains why `iter` is
+            // taken inside the loop.
+            let item = unsafe { (*iter.cast::<P::Iter<'_>>()).next_back() };
+            match item {
+                None => {
+                    self.pos.exhausted = true;
+                    return None;
+                }
+                Some(item) => if self.consume(&item, !P::REVERSED) {
+                    return Some(fun(item));
+                }
+            }
+        }
+    }
+
+    /// The part of the text not yet returned by `next_map`, `next_ref` or
+    /// `next_back_map`, without the delimiters already passed over.
+    pub fn remainder(&self) -> &S::Target
+    where
+        P: Pieces<S::Target> {
+
+        let (front, back) = self.remainder_span();
+        &self.base.get()[front .. back]
+    }
+
+    // Where `remainder` begins and ends in the text.
+    fn remainder_span(&self) -> (usize, usize)
+    where
+        P: Pieces<S::Target> {
+
+        if self.pos.exhausted {
+            return (self.pos.front, self.pos.back);
+        }
+
+        let (front, back) = self.splitter.get().trim(self.base.get(), &self.pos);
+        (front, back.max(front))
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.pos
+    }
+
+    /// Replaces the iterator with one that carries on from `checkpoint`.
+    pub fn rewind(&mut self, checkpoint: Checkpoint)
+    where
+        P: Pieces<S::Target> {
+
+        // SAFETY: As in `new`. The old iterator is dropped only once the
+        // new one exists, so a panic in `resume` leaves `self` intact.
+        unsafe {
+            let text: &'static S::Target = &*(&**self.base.get() as *const _);
+            let iter = self.splitter.get().resume(text, &checkpoint);
+            ManuallyDrop::drop(&mut self.iter);
+            self.iter = ManuallyDrop::new(iter);
+        }
+
+        self.pos = checkpoint;
+        self.stale_front = !P::EXACT_RESUME && checkpoint.taken_front > 0;
+        self.stale_back  = !P::EXACT_RESUME && checkpoint.taken_back > 0;
+    }
+
+    /// Where `remainder` begins in the text, in bytes, or for a splitter
+    /// that works from the back, how far before the end it ends.
+    pub fn offset(&self) -> usize
+    where
+        P: Pieces<S::Target> {
+
+        let (front, back) = self.remainder_span();
+        if P::REVERSED {
+            self.base.get().len() - back
+        } else {
+            front
+        }
+    }
+
+    /// Records that `item` was taken, unless it is a stale piece left over
+    /// from `rewind`, in which case it returns `false`.
+    fn consume(&mut self, item: &P::Item<'_>, from_back: bool) -> bool
+    where
+        P: Pieces<S::Target> {
+
+        let (start, end) = P::span(self.base.get(), item);
+        if self.is_stale(start, end) {
+            return false;
+        }
+
+        let pos = &mut self.pos;
+        if from_back {
+            pos.back = pos.back.min(start).max(pos.front);
+            pos.taken_back += 1;
+            self.stale_back = false;
+        } else {
+            pos.front = pos.front.max(end).min(pos.back);
+            pos.taken_front += 1;
+            self.stale_front = false;
+        }
+
+        if pos.front == pos.back {
+            self.settle();
+        }
+
+        true
+    }
+
+    fn is_stale(&mut self, start: usize, end: usize) -> bool {
+        // Two empty pieces at the same place look alike, so it doesn’t
+        // matter which of them gets dropped.
+        if start == end {
+            if self.stale_front && start == self.pos.front {
+                self.stale_front = false;
+                return true;
+            }
+            if self.stale_back && end == self.pos.back {
+                self.stale_back = false;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Works out whether an empty remainder has a piece left in it, which
+    /// depends on delimiters `pos` doesn’t record.
+    fn settle(&mut self)
+    where
+        P: Pieces<S::Target> {
+
+        // The delimiters on either side would both have to be empty and
+        // in the same place to leave room for a piece.
+        if !P::EXACT_RESUME && self.pos.taken_front > 0 && self.pos.taken_back > 0 {
+            self.pos.exhausted = true;
+            return;
+        }
+
+        // Otherwise there is at most one piece left, so look for it and
+        // then start over if it is there.
+        let found = loop {
+            let (start, end) = match self.next_span() {
+                Some(span) => span,
+                None => break false,
+            };
+            if !self.is_stale(start, end) {
+                break true;
+            }
+        };
+
+        if found {
+            self.rewind(self.pos);
+        } else {
+            self.pos.exhausted = true;
+        }
+    }
+
+    fn next_span(&mut self) -> Option<(usize, usize)>
+    where
+        P: Pieces<S::Target> {
+
+        let iter: *mut P::Iter<'static> = &mut *self.iter;
+        // SAFETY: As in `next_ref`.
+        let item = unsafe { (*iter.cast::<P::Iter<'_>>()).next()? };
+        Some(P::span(self.base.get(), &item))
+    }
+}
+
+// Cloning `base` may move the text, so the clone gets its own iterator,
+// resumed from where this one is.
+impl<S, P> Clone for SelfRef<S, P>
+where
+    S: StableDeref + Clone,
+    S::Target: Haystack,
+    P: Pieces<S::Target> + Clone, {
+
+    fn clone(&self) -> Self {
+        let mut result = SelfRef::new(self.base.get().clone(),
+                                      self.splitter.get().clone());
+        result.rewind(self.pos);
+        result
+    }
+}
+
+impl<S, P> SelfRef<S, P>
+where
+    S: CloneStableDeref<Target = str>,
+    P: BorrowIter + Clone,
+    for<'a> P::Iter<'a>: Clone, {
+
+    /// Clones `self` along with its iterator, for iterators that aren’t
+    /// `Pieces` and so can’t be resumed.
+    pub fn clone_iter(&self) -> Self {
+        // SAFETY: The cloned `base` derefs to the same text, which the
+        // iterator may keep borrowing, and a `BorrowIter` iterator doesn’t
+        // borrow the splitter.
+        let iter = unsafe { (*(&*self.iter as *const P::Iter<'static>)).clone() };
+
+        SelfRef {
+            iter:        ManuallyDrop::new(iter),
+            splitter:    Aliasable::new(self.splitter.get().clone()),
+            base:        Aliasable::new(self.base.get().clone()),
+            pos:         self.pos,
+            stale_front: self.stale_front,
+            stale_back:  self.stale_back,
+        }
+    }
+}
+
+impl<S, P> Drop for SelfRef<S, P>
+where
+    S: Deref,
+    S::Target: Haystack,
+    P: Splitter<S::Target>, {
+
+    fn drop(&mut self) {
+        // SAFETY: `iter` is never used again, and it must go before the
+        // `splitter` and `base` it borrows from, which drop after this.
+        unsafe { ManuallyDrop::drop(&mut self.iter) }
+    }
+}
+
+impl<S, P> fmt::Debug for SelfRef<S, P>
+where
+    S: Deref + fmt::Debug,
+    S::Target: Haystack,
+    P: Splitter<S::Target> + fmt::Debug, {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SelfRef")
+         .field("base", self.base.get())
+         .field("splitter", self.splitter.get())
+         .field("pos", &self.pos)
+         .finish()
+    }
+}
+
+/// Holds a `T` without asserting unique access to its referent when
+/// moved. A plain `Box<str>` field, for instance, would invalidate the
+/// iterator’s borrow of the text each time a `SelfRef` moves.
+struct Aliasable<T>(MaybeUninit<T>);
+
+impl<T> Aliasable<T> {
+    fn new(value: T) -> Self {
+        Aliasable(MaybeUninit::new(value))
+    }
+
+    fn get(&self) -> &T {
+        // SAFETY: Initialized by `new` and dropped only by `drop`.
+        unsafe { self.0.assume_init_ref() }
+    }
+
+    fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: Initialized by `new`, and `this` will not drop it.
+        unsafe { this.0.assume_init_read() }
+    }
+}
+
+impl<T> Drop for Aliasable<T> {
+    fn drop(&mut self) {
+        // SAFETY: Initialized by `new`, and this is the only drop.
+        unsafe { self.0.assume_init_drop() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::str;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Whitespace;
+
+    impl BorrowIter for Whitespace {
+        type Item<'a> = &'a str;
+        type Iter<'a> = str::SplitWhitespace<'a>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            base.split_whitespace()
+        }
+    }
+
+    impl Pieces for Whitespace {
+        fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+            item.span(base)
+        }
+    }
+
+    // Borrows a delimiter through a `Box`, as a regex splitter does.
+    #[derive(Debug)]
+    struct Boxed(Box<str>);
+
+    unsafe impl Splitter for Boxed {
+        type Item<'a> = &'a str;
+        type Iter<'a> = str::Split<'a, &'a str>;
+
+        fn split<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            // SAFETY: The delimiter is behind a `Box`.
+            base.split(unsafe { &*(&*self.0 as *const str) })
+        }
+    }
+
+    impl Pieces for Boxed {
+        fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+            item.span(base)
+        }
+    }
+
+    fn collect<S, P>(mut sr: SelfRef<S, P>) -> Vec<String>
+    where
+        S: StableDeref<Target = str>,
+        P: for<'a> Pieces<Item<'a> = &'a str>, {
+
+        let mut result = Vec::new();
+        while let Some(word) = sr.next_map(str::to_owned) {
+            result.push(word);
+        }
+        result
+    }
+
+    #[test]
+    fn box_str_moved_midway() {
+        let base: Box<str> = "a b c".into();
+        let mut sr = SelfRef::new(base, Whitespace);
+        assert_eq!( sr.next_map(str::to_owned), Some("a".to_owned()) );
+
+        let moved = Box::new(sr);
+        assert_eq!( collect(*moved), vec!["b", "c"] );
+    }
+
+    #[test]
+    fn string_and_rc() {
+        assert_eq!( collect(SelfRef::new(String::from("x  y"), Whitespace)),
+                    vec!["x", "y"] );
+
+        let rc: Rc<str> = "p q".into();
+        let sr = SelfRef::new(Rc::clone(&rc), Whitespace);
+        assert_eq!( Rc::strong_count(&rc), 2 );
+        assert_eq!( collect(sr), vec!["p", "q"] );
+        assert_eq!( Rc::strong_count(&rc), 1 );
+    }
+
+    #[test]
+    fn boxed_splitter_moved() {
+        let base = String::from("1, 2, 3");
+        let mut sr = SelfRef::new(base, Boxed(", ".into()));
+        assert_eq!( sr.next_map(str::len), Some(1) );
+
+        let sr = vec![sr].pop().unwrap();
+        assert_eq!( collect(sr), vec!["2", "3"] );
+    }
+
+    #[test]
+    fn next_ref_then_move() {
+        let mut sr = SelfRef::new(String::from("a b"), Boxed(" ".into()));
+        assert_eq!( sr.next_ref(), Some("a") );
+
+        let mut sr = Box::new(sr);
+        assert_eq!( sr.next_ref(), Some("b") );
+        assert_eq!( sr.next_ref(), None );
+    }
+
+    #[test]
+    fn remainder_from_both_ends() {
+        let mut sr = SelfRef::new(String::from("a  b c d"), Whitespace);
+        assert_eq!( sr.remainder(), "a  b c d" );
+        sr.next_ref();
+        assert_eq!( (sr.remainder(), sr.offset()), ("  b c d", 1) );
+        sr.next_back_map(|_| ());
+        assert_eq!( sr.remainder(), "  b c " );
+        sr.next_ref();
+        sr.next_back_map(|_| ());
+        assert_eq!( (sr.remainder(), sr.offset()), (" ", 4) );
+        assert_eq!( sr.next_ref(), None );
+        assert_eq!( sr.remainder(), " " );
+    }
+
+    #[test]
+    fn into_inner() {
+        let rc: Rc<str> = "x y z".into();
+        let mut sr = Box::new(SelfRef::new(Rc::clone(&rc), Boxed(" ".into())));
+        sr.next_ref();
+
+        let back = sr.into_inner();
+        assert!( Rc::ptr_eq(&rc, &back) );
+        assert_eq!( Rc::strong_count(&rc), 2 );
+    }
+
+    #[test]
+    fn dropped_partway() {
+        let mut sr = SelfRef::new(String::from("one two"), Boxed(" ".into()));
+        sr.next_map(|_| ());
+        drop(sr);
+    }
+
+    // An iterator that reads the text while being dropped, to check that
+    // the iterator goes before the owner.
+    static TOUCHED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Touchy<'a>(&'a str);
+
+    impl<'a> Iterator for Touchy<'a> {
+        type Item = &'a str;
+
+        fn next(&mut self) -> Option<&'a str> {
+            None
+        }
+    }
+
+    impl Drop for Touchy<'_> {
+        fn drop(&mut self) {
+            TOUCHED.fetch_add(self.0.len(), Ordering::SeqCst);
+        }
+    }
+
+    struct Touching;
+
+    impl BorrowIter for Touching {
+        type Item<'a> = &'a str;
+        type Iter<'a> = Touchy<'a>;
+
+        fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+            Touchy(base)
+        }
+    }
+
+    #[test]
+    fn non_str_items() {
+        struct Chars;
+
+        impl BorrowIter for Chars {
+            type Item<'a> = (usize, char);
+            type Iter<'a> = str::CharIndices<'a>;
+
+            fn iter<'a>(&self, base: &'a str) -> Self::Iter<'a> {
+                base.char_indices()
+            }
+        }
+
+        impl Pieces for Chars {
+            fn span(base: &str, item: &Self::Item<'_>) -> (usize, usize) {
+                item.span(base)
+            }
+        }
+
+        let mut sr = SelfRef::new(Box::<str>::from("€x"), Chars);
+        assert_eq!( sr.next_map(|p| p), Some((0, '€')) );
+        assert_eq!( sr.next_map(|p| p), Some((3, 'x')) );
+        assert_eq!( sr.next_map(|p| p), None );
+    }
+
+    #[test]
+    fn iter_dropped_first() {
+        let sr = SelfRef::new(Box::<str>::from("12345"), Touching);
+        drop(Box::new(sr));
+        assert_eq!( TOUCHED.load(Ordering::SeqCst), 5 );
+    }
+}
@@ -1,4 +1,7 @@
-use std::str;
+use core::{iter, str};
+
+#[cfg(feature = "alloc")]
+use alloc::vec::{self, Vec};
 
 use super::utf8::char_boundaries;
 
@@ -127,6 +130,310 @@ impl<'a, T> SplitEnd for &'a mut [T] {
     }
 }
 
+/// Splits a string or byte slice after a given number of lines.
+///
+/// Lines end with `\n` (so also `\r\n`), and the terminator stays with
+/// the line it ends. An unterminated final line counts as a line.
+pub trait SplitLines: Sized {
+    fn try_split_first_lines(self, n: usize) -> Option<(Self, Self)>;
+
+    fn try_split_last_lines(self, n: usize) -> Option<(Self, Self)>;
+
+    fn split_first_lines(self, n: usize) -> (Self, Self) {
+        self.try_split_first_lines(n)
+            .expect("SplitLines::split_first_lines: out of bounds")
+    }
+
+    fn split_last_lines(self, n: usize) -> (Self, Self) {
+        self.try_split_last_lines(n)
+            .expect("SplitLines::split_last_lines: out of bounds")
+    }
+}
+
+impl SplitLines for &str {
+    fn try_split_first_lines(self, n: usize) -> Option<(Self, Self)> {
+        line_boundaries(self.as_bytes())
+            .nth(n)
+            .map(|index| self.split_at(index))
+    }
+
+    fn try_split_last_lines(self, n: usize) -> Option<(Self, Self)> {
+        line_boundaries(self.as_bytes())
+            .rev()
+            .nth(n)
+            .map(|index| flip(self.split_at(index)))
+    }
+}
+
+impl SplitLines for &[u8] {
+    fn try_split_first_lines(self, n: usize) -> Option<(Self, Self)> {
+        line_boundaries(self)
+            .nth(n)
+            .map(|index| self.split_at(index))
+    }
+
+    fn try_split_last_lines(self, n: usize) -> Option<(Self, Self)> {
+        line_boundaries(self)
+            .rev()
+            .nth(n)
+            .map(|index| flip(self.split_at(index)))
+    }
+}
+
+/// Splits whitespace-separated words off either end of a string.
+///
+/// Like Python’s `str.split(None, n)`, the whitespace between the words
+/// taken and the remainder is dropped, but the remainder is otherwise
+/// left as it was.
+#[cfg(feature = "alloc")]
+pub trait SplitWords<'a>: Sized {
+    fn try_split_first_words(self, n: usize) -> Option<(Vec<&'a str>, &'a str)>;
+
+    fn try_split_last_words(self, n: usize) -> Option<(Vec<&'a str>, &'a str)>;
+
+    fn split_first_words(self, n: usize) -> (Vec<&'a str>, &'a str) {
+        self.try_split_first_words(n)
+            .expect("SplitWords::split_first_words: out of bounds")
+    }
+
+    fn split_last_words(self, n: usize) -> (Vec<&'a str>, &'a str) {
+        self.try_split_last_words(n)
+            .expect("SplitWords::split_last_words: out of bounds")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> SplitWords<'a> for &'a str {
+    fn try_split_first_words(self, n: usize) -> Option<(Vec<&'a str>, &'a str)> {
+        let mut words = Vec::with_capacity(n);
+        let mut rest = self.trim_start();
+
+        for _ in 0 .. n {
+            if rest.is_empty() {
+                return None;
+            }
+
+            let after = rest.trim_start_matches(|c: char| !c.is_whitespace());
+            words.push(&rest[.. rest.len() - after.len()]);
+            rest = after.trim_start();
+        }
+
+        Some((words, rest))
+    }
+
+    fn try_split_last_words(self, n: usize) -> Option<(Vec<&'a str>, &'a str)> {
+        let mut words = Vec::with_capacity(n);
+        let mut rest = self.trim_end();
+
+        for _ in 0 .. n {
+            if rest.is_empty() {
+                return None;
+            }
+
+            let before = rest.trim_end_matches(|c: char| !c.is_whitespace());
+            words.push(&rest[before.len() ..]);
+            rest = before.trim_end();
+        }
+
+        words.reverse();
+        Some((words, rest))
+    }
+}
+
+// The offsets at which lines start, plus the end of the input.
+fn line_boundaries(bytes: &[u8]) -> impl DoubleEndedIterator<Item = usize> + '_ {
+    let unterminated = bytes.last().is_some_and(|&b| b != b'\n');
+
+    iter::once(0)
+        .chain(bytes.iter()
+                    .enumerate()
+                    .filter(|&(_, &b)| b == b'\n')
+                    .map(|(i, _)| i + 1))
+        .chain(if_opt!{ unterminated, bytes.len() })
+}
+
+/// Splits a container by reference, without consuming it.
+///
+/// Each container borrows as some [`SplitEnd`] slice type, and the methods
+/// here split that borrowed slice. This lets generic code take `&str` and
+/// `String`, or `&[T]` and `Vec<T>`, alike.
+pub trait SplitEndRef {
+    type Slice<'a>: SplitEnd where Self: 'a;
+
+    fn as_split_end(&self) -> Self::Slice<'_>;
+
+    fn split_first_ref(&self)
+        -> Option<(<Self::Slice<'_> as SplitEnd>::Item, Self::Slice<'_>)> {
+        self.as_split_end().split_first()
+    }
+
+    fn split_last_ref(&self)
+        -> Option<(<Self::Slice<'_> as SplitEnd>::Item, Self::Slice<'_>)> {
+        self.as_split_end().split_last()
+    }
+
+    fn try_split_first_n_ref(&self, n: usize)
+        -> Option<(Self::Slice<'_>, Self::Slice<'_>)> {
+        self.as_split_end().try_split_first_n(n)
+    }
+
+    fn try_split_last_n_ref(&self, n: usize)
+        -> Option<(Self::Slice<'_>, Self::Slice<'_>)> {
+        self.as_split_end().try_split_last_n(n)
+    }
+
+    fn split_first_n_ref(&self, n: usize) -> (Self::Slice<'_>, Self::Slice<'_>) {
+        self.as_split_end().split_first_n(n)
+    }
+
+    fn split_last_n_ref(&self, n: usize) -> (Self::Slice<'_>, Self::Slice<'_>) {
+        self.as_split_end().split_last_n(n)
+    }
+}
+
+impl SplitEndRef for str {
+    type Slice<'a> = &'a str;
+
+    fn as_split_end(&self) -> Self::Slice<'_> {
+        self
+    }
+}
+
+impl<T> SplitEndRef for [T] {
+    type Slice<'a> = &'a [T] where T: 'a;
+
+    fn as_split_end(&self) -> Self::Slice<'_> {
+        self
+    }
+}
+
+impl<C: SplitEndRef + ?Sized> SplitEndRef for &C {
+    type Slice<'a> = C::Slice<'a> where Self: 'a;
+
+    fn as_split_end(&self) -> Self::Slice<'_> {
+        C::as_split_end(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use alloc::{boxed::Box, rc::Rc, string::String, sync::Arc, vec::Vec};
+    use super::*;
+
+    macro_rules! impl_split_end_ref {
+        (<$($par:ident),*> $t:ty => $target:ty) => {
+            impl<$($par),*> SplitEndRef for $t {
+                type Slice<'a> = &'a $target where Self: 'a;
+
+                fn as_split_end(&self) -> Self::Slice<'_> {
+                    self
+                }
+            }
+        };
+
+        ($t:ty => $target:ty) => {
+            impl_split_end_ref!(<> $t => $target);
+        };
+    }
+
+    impl_split_end_ref!(String => str);
+    impl_split_end_ref!(Box<str> => str);
+    impl_split_end_ref!(Rc<str> => str);
+    impl_split_end_ref!(Arc<str> => str);
+    impl_split_end_ref!(<T> Vec<T> => [T]);
+    impl_split_end_ref!(<T> Box<[T]> => [T]);
+    impl_split_end_ref!(<T> Rc<[T]> => [T]);
+    impl_split_end_ref!(<T> Arc<[T]> => [T]);
+}
+
+/// Adapts an arbitrary iterator to [`SplitEnd`].
+///
+/// Splitting off the first (or last) `n` items buffers them into a `Vec`;
+/// the other half continues with the underlying iterator. The
+/// `split_first` methods are available for any `Iterator`, but the
+/// `SplitEnd` impl requires a `DoubleEndedIterator`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct IterSplit<I: Iterator> {
+    buf:  vec::IntoIter<I::Item>,
+    rest: Option<I>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Iterator> IterSplit<I> {
+    pub fn new<J>(iter: J) -> Self
+    where
+        J: IntoIterator<IntoIter = I> {
+
+        IterSplit {
+            buf:  Vec::new().into_iter(),
+            rest: Some(iter.into_iter()),
+        }
+    }
+
+    fn buffered(buf: Vec<I::Item>) -> Self {
+        IterSplit {
+            buf:  buf.into_iter(),
+            rest: None,
+        }
+    }
+
+    pub fn split_first(mut self) -> Option<(I::Item, Self)> {
+        self.next().map(|item| (item, self))
+    }
+
+    pub fn try_split_first_n(mut self, n: usize) -> Option<(Self, Self)> {
+        let front: Vec<_> = self.by_ref().take(n).collect();
+        if_opt!{ front.len() == n, (Self::buffered(front), self) }
+    }
+
+    pub fn split_first_n(self, n: usize) -> (Self, Self) {
+        self.try_split_first_n(n)
+            .expect("IterSplit::split_first_n: out of bounds")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: DoubleEndedIterator> SplitEnd for IterSplit<I> {
+    type Item = I::Item;
+
+    fn split_first(self) -> Option<(Self::Item, Self)> {
+        IterSplit::split_first(self)
+    }
+
+    fn split_last(mut self) -> Option<(Self::Item, Self)> {
+        self.next_back().map(|item| (item, self))
+    }
+
+    fn try_split_first_n(self, n: usize) -> Option<(Self, Self)> {
+        IterSplit::try_split_first_n(self, n)
+    }
+
+    fn try_split_last_n(mut self, n: usize) -> Option<(Self, Self)> {
+        let mut back: Vec<_> = self.by_ref().rev().take(n).collect();
+        back.reverse();
+        if_opt!{ back.len() == n, (Self::buffered(back), self) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: Iterator> Iterator for IterSplit<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.next().or_else(|| self.rest.as_mut()?.next())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I: DoubleEndedIterator> DoubleEndedIterator for IterSplit<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rest.as_mut()
+            .and_then(DoubleEndedIterator::next_back)
+            .or_else(|| self.buf.next_back())
+    }
+}
+
 //impl<'a> SplitEnd for &'a [u8] {
 //    type Item = Result<(char, &'a [u8]), str::Utf8Error>;
 //
@@ -221,4 +528,165 @@ mod tests {
     fn split_str_n_oob() {
         "hello".split_first_n(12);
     }
+
+    #[test]
+    fn str_split_lines() {
+        let s = "one\ntwo\r\nthree";
+        assert_eq!( s.split_first_lines(0), ("", s) );
+        assert_eq!( s.split_first_lines(1), ("one\n", "two\r\nthree") );
+        assert_eq!( s.split_first_lines(2), ("one\ntwo\r\n", "three") );
+        assert_eq!( s.split_first_lines(3), (s, "") );
+        assert_eq!( s.try_split_first_lines(4), None );
+
+        assert_eq!( s.split_last_lines(0), ("", s) );
+        assert_eq!( s.split_last_lines(1), ("three", "one\ntwo\r\n") );
+        assert_eq!( s.split_last_lines(2), ("two\r\nthree", "one\n") );
+        assert_eq!( s.split_last_lines(3), (s, "") );
+        assert_eq!( s.try_split_last_lines(4), None );
+    }
+
+    #[test]
+    fn str_split_lines_terminated() {
+        let s = "a\n\nb\n";
+        assert_eq!( s.split_first_lines(2), ("a\n\n", "b\n") );
+        assert_eq!( s.split_last_lines(1), ("b\n", "a\n\n") );
+        assert_eq!( s.split_last_lines(2), ("\nb\n", "a\n") );
+        assert_eq!( s.try_split_first_lines(4), None );
+
+        assert_eq!( "".try_split_first_lines(0), Some(("", "")) );
+        assert_eq!( "".try_split_first_lines(1), None );
+        assert_eq!( "\n".split_last_lines(1), ("\n", "") );
+    }
+
+    #[test]
+    fn bytes_split_lines() {
+        let b: &[u8] = b"x\xff\ny\r\n";
+        assert_eq!( b.split_first_lines(1), (&b"x\xff\n"[..], &b"y\r\n"[..]) );
+        assert_eq!( b.split_last_lines(1), (&b"y\r\n"[..], &b"x\xff\n"[..]) );
+        assert_eq!( b.try_split_last_lines(3), None );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn str_split_words() {
+        let s = "  cmd\targ  rest  of\tline ";
+        assert_eq!( s.split_first_words(0), (vec![], "cmd\targ  rest  of\tline ") );
+        assert_eq!( s.split_first_words(1), (vec!["cmd"], "arg  rest  of\tline ") );
+        assert_eq!( s.split_first_words(2), (vec!["cmd", "arg"], "rest  of\tline ") );
+        assert_eq!( s.split_first_words(5),
+                    (vec!["cmd", "arg", "rest", "of", "line"], "") );
+        assert_eq!( s.try_split_first_words(6), None );
+
+        assert_eq!( s.split_last_words(0), (vec![], "  cmd\targ  rest  of\tline") );
+        assert_eq!( s.split_last_words(2), (vec!["of", "line"], "  cmd\targ  rest") );
+        assert_eq!( s.split_last_words(5),
+                    (vec!["cmd", "arg", "rest", "of", "line"], "") );
+        assert_eq!( s.try_split_last_words(6), None );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn str_split_words_edge_cases() {
+        assert_eq!( "".try_split_first_words(0), Some((vec![], "")) );
+        assert_eq!( "   ".try_split_first_words(1), None );
+        assert_eq!( "€ ह 𐍈".split_first_words(1), (vec!["€"], "ह 𐍈") );
+        assert_eq!( "€ ह 𐍈".split_last_words(1), (vec!["𐍈"], "€ ह") );
+        assert_eq!( "one\u{3000}two".split_first_words(1), (vec!["one"], "two") );
+    }
+
+    #[test]
+    fn split_ref_borrowed() {
+        assert_eq!( "hello".split_first_ref(), Some(('h', "ello")) );
+        assert_eq!( [1, 2, 3][..].split_last_ref(), Some((&3, &[1, 2][..])) );
+        assert_eq!( "bye".split_first_n_ref(1), ("b", "ye") );
+        assert_eq!( "bye".split_last_n_ref(1), ("e", "by") );
+        assert_eq!( "bye".try_split_first_n_ref(4), None );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn split_ref_owned() {
+        use std::rc::Rc;
+
+        let s = String::from("€6");
+        assert_eq!( s.split_first_ref(), Some(('€', "6")) );
+        assert_eq!( s.split_first_n_ref(1), ("€", "6") );
+        assert_eq!( s, "€6" );
+
+        let b: Box<str> = "bye".into();
+        assert_eq!( b.split_last_n_ref(2), ("ye", "b") );
+
+        let r: Rc<str> = "bye".into();
+        assert_eq!( r.split_last_ref(), Some(('e', "by")) );
+
+        let v = vec![1, 2, 3];
+        assert_eq!( v.split_first_n_ref(2), (&[1, 2][..], &[3][..]) );
+        assert_eq!( v.try_split_last_n_ref(4), None );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn split_ref_generic() {
+        fn head<S: SplitEndRef + ?Sized>(s: &S)
+            -> Option<<S::Slice<'_> as SplitEnd>::Item> {
+            s.split_first_ref().map(|(c, _)| c)
+        }
+
+        assert_eq!( head("abc"), Some('a') );
+        assert_eq!( head(&String::from("abc")), Some('a') );
+        assert_eq!( head(&&"abc"), Some('a') );
+        assert_eq!( head(&vec![7, 8]), Some(&7) );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_split() {
+        let (x, rest) = IterSplit::new(1 .. 5).split_first().unwrap();
+        assert_eq!( x, 1 );
+        assert_eq!( rest.collect::<Vec<_>>(), vec![2, 3, 4] );
+
+        let (x, rest) = SplitEnd::split_last(IterSplit::new(1 .. 5)).unwrap();
+        assert_eq!( x, 4 );
+        assert_eq!( rest.collect::<Vec<_>>(), vec![1, 2, 3] );
+
+        assert!( IterSplit::new(0 .. 0).split_first().is_none() );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_split_n() {
+        let (front, back) = IterSplit::new(1 .. 6).split_first_n(2);
+        assert_eq!( front.collect::<Vec<_>>(), vec![1, 2] );
+        assert_eq!( back.collect::<Vec<_>>(), vec![3, 4, 5] );
+
+        let (back, front) = IterSplit::new(1 .. 6).split_last_n(2);
+        assert_eq!( back.collect::<Vec<_>>(), vec![4, 5] );
+        assert_eq!( front.collect::<Vec<_>>(), vec![1, 2, 3] );
+
+        let (front, back) = IterSplit::new(1 .. 4).split_first_n(3);
+        assert_eq!( front.collect::<Vec<_>>(), vec![1, 2, 3] );
+        assert_eq!( back.count(), 0 );
+
+        assert!( IterSplit::new(1 .. 4).try_split_first_n(4).is_none() );
+        assert!( IterSplit::new(1 .. 4).try_split_last_n(4).is_none() );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_split_forward_only() {
+        let words = "a b c".split_whitespace().filter(|w| *w != "b");
+        let (front, back) = IterSplit::new(words).split_first_n(1);
+        assert_eq!( front.collect::<Vec<_>>(), vec!["a"] );
+        assert_eq!( back.collect::<Vec<_>>(), vec!["c"] );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_split_repeated() {
+        let (front, back) = IterSplit::new(1 .. 8).split_first_n(5);
+        let (last, init) = SplitEnd::split_last_n(front, 2);
+        assert_eq!( last.collect::<Vec<_>>(), vec![4, 5] );
+        assert_eq!( init.collect::<Vec<_>>(), vec![1, 2, 3] );
+        assert_eq!( back.collect::<Vec<_>>(), vec![6, 7] );
+    }
 }
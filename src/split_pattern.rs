@@ -0,0 +1,1189 @@
+//! The regex splitting that every regex backend shares.
+//!
+//! A backend is a regex type that implements `SplitPattern`: the `regex`
+//! crate’s `Regex` and `bytes::Regex` with the `regex` feature,
+//! `regex_lite::Regex` with `regex-lite`, and `fancy_regex::Regex` with
+//! `fancy-regex`.
+
+use std::any::{Any, TypeId};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[cfg(feature = "regex")]
+use regex_automata::{Anchored, Input};
+use stable_deref_trait::StableDeref;
+
+use crate::haystack::Haystack;
+
+/// A regex that [`RegexSplit`](crate::RegexSplit) and the owning
+/// `into_split_regex` methods can split on. Each regex backend feature
+/// implements it for its regex type.
+///
+/// The owning `into_captures_iter` and `into_tokens` methods are not
+/// generic over it, and need the `regex` feature: the first takes only a
+/// `regex::Regex`, since its closures see `regex::Captures`, and the
+/// second lexes with a `Lexer`, which is built on `regex_automata`.
+pub trait SplitPattern: Sized {
+    type Text: ?Sized + Haystack;
+
+    /// Why a search failed: `Infallible` for a backend whose searches
+    /// always finish.
+    type Error: Error;
+
+    /// The backend’s own match, such as `regex::Match`, which the owning
+    /// `into_find_iter_map` and `into_find_iter_and_then` pass on.
+    type Match<'h> where Self::Text: 'h;
+
+    /// The pattern the regex was compiled from.
+    fn as_str(&self) -> &str;
+
+    /// Finds the leftmost match starting at or after `start`. The rest
+    /// of `text` is context for anchors and word boundaries.
+    fn find_match_at<'h>(&self, text: &'h Self::Text, start: usize)
+                         -> Result<Option<Self::Match<'h>>, Self::Error>;
+
+    /// Where `found` begins and ends, in bytes.
+    fn match_span(found: &Self::Match<'_>) -> (usize, usize);
+
+    /// The text `found` matched.
+    fn match_text<'h>(found: &Self::Match<'h>) -> &'h Self::Text;
+
+    /// Like `find_match_at`, but gives just the match’s span.
+    fn find_at(&self, text: &Self::Text, start: usize)
+               -> Result<Option<(usize, usize)>, Self::Error> {
+        Ok(self.find_match_at(text, start)?.map(|found| Self::match_span(&found)))
+    }
+
+    /// Finds the match the regex prefers starting right at `start`, if it
+    /// has one there. A backend that can anchor its searches stops as
+    /// soon as it knows there is none; by default this searches from
+    /// `start` and drops a match that begins later, which reads the rest
+    /// of the text on a miss.
+    fn find_anchored_at(&self, text: &Self::Text, start: usize)
+                        -> Result<Option<(usize, usize)>, Self::Error> {
+        Ok(self.find_at(text, start)?.filter(|&(found, _)| found == start))
+    }
+
+    /// Like `find_at`, but gives the span of every capture group, with
+    /// the whole match as group 0 and `None` for a group that took no
+    /// part in the match.
+    fn captures_at(&self, text: &Self::Text, start: usize)
+                   -> Result<Option<Groups>, Self::Error>;
+}
+
+/// The spans of the capture groups of one match, as `captures_at` gives
+/// them.
+pub type Groups = Vec<Option<(usize, usize)>>;
+
+// Searches with a backend whose searches always finish.
+#[cfg(all(feature = "regex", feature = "rayon"))]
+pub(crate) fn find_infallible<R>(regex: &R, text: &R::Text, start: usize)
+                                 -> Option<(usize, usize)>
+where
+    R: SplitPattern<Error = Infallible>, {
+
+    match regex.find_at(text, start) {
+        Ok(found) => found,
+        Err(never) => match never {},
+    }
+}
+
+#[cfg(feature = "regex")]
+impl SplitPattern for regex::Regex {
+    type Text = str;
+    type Error = Infallible;
+    type Match<'h> = regex::Match<'h>;
+
+    fn as_str(&self) -> &str {
+        regex::Regex::as_str(self)
+    }
+
+    fn find_match_at<'h>(&self, text: &'h str, start: usize)
+                         -> Result<Option<regex::Match<'h>>, Infallible> {
+        Ok(regex::Regex::find_at(self, text, start))
+    }
+
+    fn match_span(found: &regex::Match<'_>) -> (usize, usize) {
+        (found.start(), found.end())
+    }
+
+    fn match_text<'h>(found: &Self::Match<'h>) -> &'h str {
+        found.as_str()
+    }
+
+    fn captures_at(&self, text: &str, start: usize)
+                   -> Result<Option<Groups>, Infallible> {
+        let caps = regex::Regex::captures_at(self, text, start);
+        Ok(caps.map(|caps| caps.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect()))
+    }
+}
+
+#[cfg(feature = "regex")]
+impl SplitPattern for regex::bytes::Regex {
+    type Text = [u8];
+    type Error = Infallible;
+    type Match<'h> = regex::bytes::Match<'h>;
+
+    fn as_str(&self) -> &str {
+        regex::bytes::Regex::as_str(self)
+    }
+
+    fn find_match_at<'h>(&self, text: &'h [u8], start: usize)
+                         -> Result<Option<regex::bytes::Match<'h>>, Infallible> {
+        Ok(regex::bytes::Regex::find_at(self, text, start))
+    }
+
+    fn match_span(found: &regex::bytes::Match<'_>) -> (usize, usize) {
+        (found.start(), found.end())
+    }
+
+    fn match_text<'h>(found: &Self::Match<'h>) -> &'h [u8] {
+        found.as_bytes()
+    }
+
+    fn captures_at(&self, text: &[u8], start: usize)
+                   -> Result<Option<Groups>, Infallible> {
+        let caps = regex::bytes::Regex::captures_at(self, text, start);
+        Ok(caps.map(|caps| caps.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect()))
+    }
+}
+
+/// `regex_automata`’s `meta::Regex`, the engine inside `regex::Regex`,
+/// which unlike it can anchor a search, so `split_first_regex` gives up
+/// at once on a miss. It doesn’t keep its pattern, so `as_str` is empty.
+/// Its matches come with the text searched.
+#[cfg(feature = "regex")]
+impl SplitPattern for regex_automata::meta::Regex {
+    type Text = str;
+    type Error = Infallible;
+    type Match<'h> = (&'h str, regex_automata::Match);
+
+    fn as_str(&self) -> &str {
+        ""
+    }
+
+    fn find_match_at<'h>(&self, text: &'h str, start: usize)
+                         -> Result<Option<Self::Match<'h>>, Infallible> {
+        let input = Input::new(text).range(start ..);
+        Ok(self.search(&input).map(|found| (text, found)))
+    }
+
+    fn match_span(found: &Self::Match<'_>) -> (usize, usize) {
+        (found.1.start(), found.1.end())
+    }
+
+    fn match_text<'h>(found: &Self::Match<'h>) -> &'h str {
+        &found.0[found.1.range()]
+    }
+
+    fn find_anchored_at(&self, text: &str, start: usize)
+                        -> Result<Option<(usize, usize)>, Infallible> {
+        let input = Input::new(text).range(start ..).anchored(Anchored::Yes);
+        Ok(self.search(&input).map(|found| (found.start(), found.end())))
+    }
+
+    fn captures_at(&self, text: &str, start: usize)
+                   -> Result<Option<Groups>, Infallible> {
+        let mut caps = self.create_captures();
+        self.search_captures(&Input::new(text).range(start ..), &mut caps);
+        if !caps.is_match() {
+            return Ok(None);
+        }
+        Ok(Some((0 .. caps.group_len())
+                .map(|i| caps.get_group(i).map(|span| (span.start, span.end)))
+                .collect()))
+    }
+}
+
+#[cfg(feature = "regex-lite")]
+impl SplitPattern for regex_lite::Regex {
+    type Text = str;
+    type Error = Infallible;
+    type Match<'h> = regex_lite::Match<'h>;
+
+    fn as_str(&self) -> &str {
+        regex_lite::Regex::as_str(self)
+    }
+
+    fn find_match_at<'h>(&self, text: &'h str, start: usize)
+                         -> Result<Option<regex_lite::Match<'h>>, Infallible> {
+        Ok(regex_lite::Regex::find_at(self, text, start))
+    }
+
+    fn match_span(found: &regex_lite::Match<'_>) -> (usize, usize) {
+        (found.start(), found.end())
+    }
+
+    fn match_text<'h>(found: &Self::Match<'h>) -> &'h str {
+        found.as_str()
+    }
+
+    fn captures_at(&self, text: &str, start: usize)
+                   -> Result<Option<Groups>, Infallible> {
+        let caps = regex_lite::Regex::captures_at(self, text, start);
+        Ok(caps.map(|caps| caps.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect()))
+    }
+}
+
+/// A search fails if `fancy_regex` gives up on a match, as it does when
+/// backtracking runs past its limit.
+#[cfg(feature = "fancy-regex")]
+impl SplitPattern for fancy_regex::Regex {
+    type Text = str;
+    type Error = fancy_regex::Error;
+    type Match<'h> = fancy_regex::Match<'h>;
+
+    fn as_str(&self) -> &str {
+        fancy_regex::Regex::as_str(self)
+    }
+
+    fn find_match_at<'h>(&self, text: &'h str, start: usize)
+                         -> Result<Option<fancy_regex::Match<'h>>, fancy_regex::Error> {
+        fancy_regex::Regex::find_from_pos(self, text, start)
+    }
+
+    fn match_span(found: &fancy_regex::Match<'_>) -> (usize, usize) {
+        (found.start(), found.end())
+    }
+
+    fn match_text<'h>(found: &Self::Match<'h>) -> &'h str {
+        found.as_str()
+    }
+
+    fn captures_at(&self, text: &str, start: usize)
+                   -> Result<Option<Groups>, fancy_regex::Error> {
+        let caps = fancy_regex::Regex::captures_from_pos(self, text, start)?;
+        Ok(caps.map(|caps| caps.iter().map(|m| m.map(|m| (m.start(), m.end()))).collect()))
+    }
+}
+
+// Where to look for the next match. Like `regex::Matches`, it skips an
+// empty match right where the previous one ended.
+#[derive(Clone, Debug)]
+pub(crate) struct Cursor {
+    pub(crate) search: usize,
+    last_end: Option<usize>,
+    // Where matches have to end by.
+    back:     usize,
+}
+
+impl Cursor {
+    pub(crate) fn new(front: usize, back: usize) -> Self {
+        Cursor { search: front, last_end: None, back }
+    }
+
+    // Finds the next match in `text` with `find`, which searches from the
+    // given position. After an empty match it steps past the next `char`,
+    // since not every backend can start a search inside one.
+    #[cfg_attr(not(feature = "regex"), allow(dead_code))]
+    pub(crate) fn next<T, M>(&mut self,
+                             text: &T,
+                             mut find: impl FnMut(usize) -> Option<M>,
+                             span: impl Fn(&M) -> (usize, usize)) -> Option<M>
+    where
+        T: ?Sized + Haystack, {
+
+        match self.try_next(text, |at| Ok::<_, Infallible>(find(at)), span) {
+            Ok(found) => found,
+            Err(never) => match never {},
+        }
+    }
+
+    // Like `next`, but with a `find` that can fail.
+    pub(crate) fn try_next<T, M, E>(&mut self,
+                                    text: &T,
+                                    mut find: impl FnMut(usize) -> Result<Option<M>, E>,
+                                    span: impl Fn(&M) -> (usize, usize))
+                                    -> Result<Option<M>, E>
+    where
+        T: ?Sized + Haystack, {
+
+        loop {
+            let m = match find(self.search)? {
+                Some(m) => m,
+                None => return Ok(None),
+            };
+            let (start, end) = span(&m);
+            if end > self.back {
+                return Ok(None);
+            }
+
+            if start == end && Some(end) == self.last_end {
+                if end == text.len() {
+                    return Ok(None);
+                }
+                self.search = text.next_start(end);
+                continue;
+            }
+
+            self.resume_after(end);
+            return Ok(Some(m));
+        }
+    }
+
+    // Whether `next` would pass over the match `span` if it found it.
+    #[cfg_attr(not(all(feature = "rayon", feature = "regex")), allow(dead_code))]
+    pub(crate) fn skips(&self, (start, end): (usize, usize)) -> bool {
+        start == end && Some(end) == self.last_end
+    }
+
+    // Carries on as though `next` had just found a match ending at `end`.
+    pub(crate) fn resume_after(&mut self, end: usize) {
+        self.search = end;
+        self.last_end = Some(end);
+    }
+}
+
+/// Like `regex::Split`, but double-ended, able to start partway through
+/// the text, and able to say where it has got to. It splits on any
+/// [`SplitPattern`], so it works the same with each regex backend.
+///
+/// Splitting from the back has to find all the remaining matches first,
+/// since regexes only search forward. Its `Debug` output shows the
+/// pattern and the position.
+///
+/// If a search fails, as one with `fancy_regex` can, splitting stops
+/// there: `next` and `next_back` return `None`, the `remainder` is the
+/// text left unsplit, and `take_error` gives the error.
+///
+#[cfg_attr(feature = "regex", doc = "```")]
+#[cfg_attr(not(feature = "regex"), doc = "```ignore")]
+/// use split_ext::RegexSplit;
+///
+/// let comma = regex::Regex::new(" *, *").unwrap();
+/// let mut fields = RegexSplit::new(&comma, "a, b ,c");
+/// assert_eq!( fields.next(), Some("a") );
+/// assert_eq!( fields.position(), 3 );
+/// assert_eq!( fields.remainder(), "b ,c" );
+/// assert_eq!( format!("{:?}", fields),
+///             r#"Split { pattern: " *, *", position: 3 }"# );
+/// ```
+pub struct Split<'r, 'b, R: SplitPattern> {
+    regex:    &'r R,
+    text:     &'b R::Text,
+    cursor:   Cursor,
+    // Delimiters found but not yet consumed, in order.
+    pending:  VecDeque<(usize, usize)>,
+    // Whether `pending` holds every remaining delimiter.
+    drained:  bool,
+    // Unyielded pieces lie within `front .. back`.
+    front:    usize,
+    back:     usize,
+    done:     bool,
+    // Whether a search failed, which ends the split.
+    failed:   bool,
+    error:    Option<R::Error>,
+}
+
+impl<'r, 'b, R: SplitPattern> Split<'r, 'b, R> {
+    pub fn new(regex: &'r R, text: &'b R::Text) -> Self {
+        Split::within(regex, text, 0, text.len())
+    }
+
+    /// Splits `text[front .. back]`, with the rest of `text` as context
+    /// for anchors and word boundaries.
+    pub fn within(regex: &'r R, text: &'b R::Text, front: usize, back: usize)
+                  -> Self {
+        assert!(front <= back && back <= text.len(),
+                "re::Split::within: out of bounds");
+        Split {
+            regex,
+            text,
+            cursor:   Cursor::new(front, back),
+            pending:  VecDeque::new(),
+            drained:  false,
+            front,
+            back,
+            done:     false,
+            failed:   false,
+            error:    None,
+        }
+    }
+
+    /// Where the next piece from the front begins, in bytes from the
+    /// start of the text.
+    pub fn position(&self) -> usize {
+        self.front
+    }
+
+    /// The part of the text not yet split off, between the pieces taken
+    /// from either end.
+    pub fn remainder(&self) -> &'b R::Text {
+        if self.done {
+            &self.text[self.back .. self.back]
+        } else {
+            &self.text[self.front .. self.back]
+        }
+    }
+
+    /// The regex being split on.
+    pub fn regex(&self) -> &'r R {
+        self.regex
+    }
+
+    /// Takes the error from the search that stopped splitting early, if
+    /// one failed.
+    pub fn take_error(&mut self) -> Option<R::Error> {
+        self.error.take()
+    }
+
+    fn find(&mut self) -> Option<(usize, usize)> {
+        let (regex, text) = (self.regex, self.text);
+        match self.cursor.try_next(text, |at| regex.find_at(text, at), |&m| m) {
+            Ok(found) => found,
+            Err(error) => {
+                // What comes after the failed search can’t be split.
+                self.pending.clear();
+                self.drained = true;
+                self.failed = true;
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    fn last_piece(&mut self) -> Option<&'b R::Text> {
+        if self.done || self.failed {
+            None
+        } else {
+            self.done = true;
+            Some(&self.text[self.front .. self.back])
+        }
+    }
+}
+
+impl<'r, 'b, R: SplitPattern> Iterator for Split<'r, 'b, R> {
+    type Item = &'b R::Text;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() && !self.drained {
+            if let Some(delim) = self.find() {
+                self.pending.push_back(delim);
+            } else {
+                self.drained = true;
+            }
+        }
+
+        match self.pending.pop_front() {
+            Some((start, end)) => {
+                let piece = &self.text[self.front .. start];
+                self.front = end;
+                Some(piece)
+            }
+            None => self.last_piece(),
+        }
+    }
+}
+
+impl<'r, 'b, R: SplitPattern> DoubleEndedIterator for Split<'r, 'b, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if !self.drained {
+            while let Some(delim) = self.find() {
+                self.pending.push_back(delim);
+            }
+            self.drained = true;
+        }
+
+        match self.pending.pop_back() {
+            Some((start, end)) => {
+                let piece = &self.text[end .. self.back];
+                self.back = start;
+                Some(piece)
+            }
+            None => self.last_piece(),
+        }
+    }
+}
+
+impl<'r, 'b, R: SplitPattern> fmt::Debug for Split<'r, 'b, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Split")
+         .field("pattern", &self.regex.as_str())
+         .field("position", &self.front)
+         .finish()
+    }
+}
+
+/// Like `regex::Matches`, but for any backend, double-ended, and able to
+/// start partway through the text.
+///
+/// As with `Split`, taking matches from the back finds all the remaining
+/// ones first, and a failed search ends the matches.
+#[cfg_attr(not(feature = "into"), allow(dead_code))]
+pub struct Matches<'r, 'h, R: SplitPattern<Text = str>> {
+    regex:   &'r R,
+    text:    &'h str,
+    cursor:  Cursor,
+    // Matches found but not yet returned, once `next_back` has been called.
+    pending: VecDeque<R::Match<'h>>,
+    drained: bool,
+    error:   Option<R::Error>,
+}
+
+#[cfg_attr(not(feature = "into"), allow(dead_code))]
+impl<'r, 'h, R: SplitPattern<Text = str>> Matches<'r, 'h, R> {
+    /// Finds the matches within `text[front .. back]`, with the rest of
+    /// `text` as context.
+    pub fn within(regex: &'r R, text: &'h str, front: usize, back: usize) -> Self {
+        assert!(front <= back && back <= text.len(),
+                "split_pattern::Matches::within: out of bounds");
+        Matches {
+            regex,
+            text,
+            cursor:  Cursor::new(front, back),
+            pending: VecDeque::new(),
+            drained: false,
+            error:   None,
+        }
+    }
+
+    /// Takes the error from the search that ended the matches early, if
+    /// one failed.
+    pub fn take_error(&mut self) -> Option<R::Error> {
+        self.error.take()
+    }
+
+    fn find(&mut self) -> Option<R::Match<'h>> {
+        let (regex, text) = (self.regex, self.text);
+        match self.cursor.try_next(text, |at| regex.find_match_at(text, at), R::match_span) {
+            Ok(found) => found,
+            Err(error) => {
+                self.pending.clear();
+                self.drained = true;
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> Iterator for Matches<'r, 'h, R> {
+    type Item = R::Match<'h>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.drained {
+            self.pending.pop_front()
+        } else {
+            self.find()
+        }
+    }
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> DoubleEndedIterator for Matches<'r, 'h, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if !self.drained {
+            while let Some(found) = self.find() {
+                self.pending.push_back(found);
+            }
+            self.drained = true;
+        }
+
+        self.pending.pop_back()
+    }
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> fmt::Debug for Matches<'r, 'h, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Matches")
+         .field("pattern", &self.regex.as_str())
+         .field("position", &self.cursor.search)
+         .finish()
+    }
+}
+
+/// Splits a string on a regex like `Regex::split`, but keeps the
+/// delimiters, as `re.split` does in Python and `String.split` in
+/// JavaScript; returned by [`SplitRegexKeep::split_regex_keep`].
+///
+/// After each piece come the capture groups of the delimiter that ends
+/// it, in order, leaving out any group that didn’t take part in the
+/// match. If the regex has no groups, the whole delimiter comes instead.
+///
+/// Splitting from the back has to find all the remaining matches first.
+/// As with `Split`, a failed search ends the split.
+pub struct SplitKeep<'r, 'h, R: SplitPattern<Text = str>> {
+    regex:  &'r R,
+    text:   &'h str,
+    cursor: Cursor,
+    // Where the next piece begins, and where the last one ends.
+    front:  usize,
+    back:   usize,
+    // Pieces and delimiters found but not yet returned, in order.
+    queue:  VecDeque<&'h str>,
+    done:   bool,
+    error:  Option<R::Error>,
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> SplitKeep<'r, 'h, R> {
+    pub fn new(regex: &'r R, text: &'h str) -> Self {
+        SplitKeep {
+            regex,
+            text,
+            cursor: Cursor::new(0, text.len()),
+            front:  0,
+            back:   text.len(),
+            queue:  VecDeque::new(),
+            done:   false,
+            error:  None,
+        }
+    }
+
+    // Starts over as though `taken_front` items had been taken from the
+    // front and `taken_back` from the back. This splits the text up to
+    // there again: one delimiter gives several items, and its groups may
+    // nest or overlap, so a byte offset doesn’t say which of them are
+    // left, nor where the search for the next delimiter began. Taking
+    // any items from the back means finding every match, as `next_back`
+    // does.
+    #[cfg_attr(not(feature = "into"), allow(dead_code))]
+    pub(crate) fn replay(regex: &'r R, text: &'h str,
+                         taken_front: usize, taken_back: usize) -> Self {
+        let mut iter = SplitKeep::new(regex, text);
+        if taken_front > 0 {
+            iter.nth(taken_front - 1);
+        }
+        if taken_back > 0 {
+            iter.nth_back(taken_back - 1);
+        }
+        iter
+    }
+
+    /// Takes the error from the search that stopped splitting early, if
+    /// one failed.
+    pub fn take_error(&mut self) -> Option<R::Error> {
+        self.error.take()
+    }
+
+    // Queues the piece before the next delimiter and the delimiter’s
+    // groups, or else the last piece. Returns whether there was any.
+    fn advance(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+
+        let (regex, text) = (self.regex, self.text);
+        let whole = |groups: &Groups| {
+            groups[0].expect("group 0 always matches")
+        };
+        match self.cursor.try_next(text, |at| regex.captures_at(text, at), whole) {
+            Ok(Some(groups)) => {
+                let (start, end) = whole(&groups);
+                self.queue.push_back(&text[self.front .. start]);
+                if groups.len() == 1 {
+                    self.queue.push_back(&text[start .. end]);
+                } else {
+                    self.queue.extend(groups[1 ..].iter().flatten().map(|&(s, e)| &text[s .. e]));
+                }
+                self.front = end;
+            }
+            Ok(None) => {
+                self.queue.push_back(&text[self.front .. self.back]);
+                self.done = true;
+            }
+            Err(error) => {
+                // What comes after the failed search can’t be split.
+                self.queue.clear();
+                self.done = true;
+                self.error = Some(error);
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn fill(&mut self) {
+        while self.advance() { }
+    }
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> Iterator for SplitKeep<'r, 'h, R> {
+    type Item = &'h str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.queue.is_empty() {
+            self.advance();
+        }
+        self.queue.pop_front()
+    }
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> DoubleEndedIterator for SplitKeep<'r, 'h, R> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.fill();
+        self.queue.pop_back()
+    }
+}
+
+impl<'r, 'h, R: SplitPattern<Text = str>> fmt::Debug for SplitKeep<'r, 'h, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SplitKeep")
+         .field("pattern", &self.regex.as_str())
+         .field("position", &self.front)
+         .finish()
+    }
+}
+
+/// Splits borrowed strings on a regex from any backend, keeping the
+/// delimiters.
+///
+#[cfg_attr(feature = "regex", doc = "```")]
+#[cfg_attr(not(feature = "regex"), doc = "```ignore")]
+/// use split_ext::SplitRegexKeep;
+///
+/// let ops = regex::Regex::new(r"\s*([+*])\s*").unwrap();
+/// let tokens: Vec<_> = "1+2 * 3".split_regex_keep(&ops).collect();
+/// assert_eq!( tokens, ["1", "+", "2", "*", "3"] );
+/// ```
+pub trait SplitRegexKeep<'h> {
+    fn split_regex_keep<'r, R>(self, regex: &'r R) -> SplitKeep<'r, 'h, R>
+    where
+        R: SplitPattern<Text = str>;
+}
+
+impl<'h> SplitRegexKeep<'h> for &'h str {
+    fn split_regex_keep<'r, R>(self, regex: &'r R) -> SplitKeep<'r, 'h, R>
+    where
+        R: SplitPattern<Text = str>, {
+
+        SplitKeep::new(regex, self)
+    }
+}
+
+/// Splits a match of a regex off the start or end of a string, a `Chars`
+/// or (with `regex::bytes::Regex`) a byte string, returning the match and
+/// the rest, like `SplitEnd::split_first_n`.
+///
+/// The match must start at the very start, or end at the very end. At
+/// the end, it is the leftmost of the matches the regex would find
+/// starting at each position that reaches the end, so `a|ab` doesn’t
+/// split `ab` off the end of `"ab"`, since it prefers `a` there. A regex
+/// that matches the empty string always succeeds.
+///
+/// The `try_` methods work with any backend, returning the error if a
+/// search fails; the others need one whose searches can’t.
+///
+/// At the start, a backend that can anchor its searches, such as
+/// `regex_automata::meta::Regex`, reads only as far as the match could
+/// reach, so peeling tokens off a long string one at a time stays linear.
+/// The others, `regex::Regex` among them, read on to the end of the text
+/// when there is no match. At the end, each match found that doesn’t
+/// reach the end costs another search.
+///
+#[cfg_attr(feature = "regex", doc = "```")]
+#[cfg_attr(not(feature = "regex"), doc = "```ignore")]
+/// use regex::Regex;
+/// use split_ext::SplitRegexEnd;
+///
+/// let number = Regex::new(r"\d+").unwrap();
+/// let space = Regex::new(r"\s*").unwrap();
+///
+/// let (n, rest) = "12 + 345".split_first_regex(&number).unwrap();
+/// assert_eq!( (n, rest), ("12", " + 345") );
+/// let (_, rest) = rest.split_first_regex(&space).unwrap();
+/// assert_eq!( rest.split_first_regex(&number), None );
+///
+/// assert_eq!( "12 + 345".split_last_regex(&number), Some(("345", "12 + ")) );
+/// ```
+pub trait SplitRegexEnd<R: SplitPattern>: Sized {
+    fn try_split_first_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error>;
+
+    fn try_split_last_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error>;
+
+    fn split_first_regex(self, regex: &R) -> Option<(Self, Self)>
+    where
+        R: SplitPattern<Error = Infallible>, {
+
+        match self.try_split_first_regex(regex) {
+            Ok(found) => found,
+            Err(never) => match never {},
+        }
+    }
+
+    fn split_last_regex(self, regex: &R) -> Option<(Self, Self)>
+    where
+        R: SplitPattern<Error = Infallible>, {
+
+        match self.try_split_last_regex(regex) {
+            Ok(found) => found,
+            Err(never) => match never {},
+        }
+    }
+}
+
+// Finds where `regex` matches at the start or end of `text`. At the end,
+// a search from some position finds the match the regex prefers at the
+// first position from there where it matches at all, so each search
+// either ends at the end or rules out every start up to the one found.
+fn find_anchored<R: SplitPattern>(regex: &R, text: &R::Text, at_start: bool)
+                                  -> Result<Option<(usize, usize)>, R::Error> {
+    if at_start {
+        return regex.find_anchored_at(text, 0);
+    }
+
+    let mut at = 0;
+    loop {
+        let (start, end) = match regex.find_at(text, at)? {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        if end == text.len() {
+            return Ok(Some((start, end)));
+        }
+        at = text.next_start(start);
+    }
+}
+
+impl<R: SplitPattern<Text = str>> SplitRegexEnd<R> for &str {
+    fn try_split_first_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error> {
+        let found = find_anchored(regex, self, true)?;
+        Ok(found.map(|(_, end)| self.split_at(end)))
+    }
+
+    fn try_split_last_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error> {
+        let found = find_anchored(regex, self, false)?;
+        Ok(found.map(|(start, _)| {
+            let (rest, matched) = self.split_at(start);
+            (matched, rest)
+        }))
+    }
+}
+
+impl<R: SplitPattern<Text = str>> SplitRegexEnd<R> for std::str::Chars<'_> {
+    fn try_split_first_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error> {
+        let found = self.as_str().try_split_first_regex(regex)?;
+        Ok(found.map(|(matched, rest)| (matched.chars(), rest.chars())))
+    }
+
+    fn try_split_last_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error> {
+        let found = self.as_str().try_split_last_regex(regex)?;
+        Ok(found.map(|(matched, rest)| (matched.chars(), rest.chars())))
+    }
+}
+
+impl<R: SplitPattern<Text = [u8]>> SplitRegexEnd<R> for &[u8] {
+    fn try_split_first_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error> {
+        let found = find_anchored(regex, self, true)?;
+        Ok(found.map(|(_, end)| self.split_at(end)))
+    }
+
+    fn try_split_last_regex(self, regex: &R) -> Result<Option<(Self, Self)>, R::Error> {
+        let found = find_anchored(regex, self, false)?;
+        Ok(found.map(|(start, _)| {
+            let (rest, matched) = self.split_at(start);
+            (matched, rest)
+        }))
+    }
+}
+
+// How many compiled patterns `cached` keeps, of all the backends.
+const CACHE_SIZE: usize = 64;
+
+// A compiled pattern, with the type it was compiled as.
+type Cached = (TypeId, Box<str>, Arc<dyn Any + Send + Sync>);
+
+// Compiled patterns, least recently used first.
+static CACHE: Mutex<Vec<Cached>> = Mutex::new(Vec::new());
+
+/// Compiles `pattern` as an `R`, or returns the regex compiled for it
+/// earlier if it is among the `CACHE_SIZE` most recently used.
+#[cfg_attr(not(feature = "into"), allow(dead_code))]
+pub(crate) fn cached<R>(pattern: &str) -> Result<Arc<R>, R::Err>
+where
+    R: FromStr + Send + Sync + 'static, {
+
+    cached_in(&CACHE, pattern)
+}
+
+fn cached_in<R>(cache: &Mutex<Vec<Cached>>, pattern: &str) -> Result<Arc<R>, R::Err>
+where
+    R: FromStr + Send + Sync + 'static, {
+
+    if let Some(regex) = reuse(&mut cache.lock().unwrap_or_else(PoisonError::into_inner),
+                               pattern) {
+        return Ok(regex);
+    }
+
+    // Compiled without the lock, so other threads needn’t wait for it.
+    let regex = Arc::new(pattern.parse::<R>()?);
+
+    let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    // Another thread may have compiled the same pattern meanwhile.
+    if let Some(regex) = reuse(&mut cache, pattern) {
+        return Ok(regex);
+    }
+    if cache.len() == CACHE_SIZE {
+        cache.remove(0);
+    }
+    cache.push((TypeId::of::<R>(), pattern.into(), Arc::clone(&regex) as _));
+    Ok(regex)
+}
+
+// Returns the `R` cached for `pattern`, if any, marking it as the most
+// recently used.
+fn reuse<R>(cache: &mut Vec<Cached>, pattern: &str) -> Option<Arc<R>>
+where
+    R: Send + Sync + 'static, {
+
+    let i = cache.iter()
+        .position(|(ty, p, _)| *ty == TypeId::of::<R>() && &**p == pattern)?;
+    let entry = cache.remove(i);
+    let regex = Arc::clone(&entry.2).downcast().ok();
+    cache.push(entry);
+    regex
+}
+
+/// Allows passing either a borrowed or an owned regex to a method that
+/// needs to own a stable pointer to it.
+///
+/// It is implemented for each backend’s regex type, which gets boxed, and
+/// for references, `Box`es, `Rc`s and `Arc`s of it, which are kept as
+/// they are. For a regex in a lazy static, pass `&*STATIC`. The type
+/// parameter is the text the regex searches, so `IntoRegex<[u8]>` is
+/// implemented for `regex::bytes::Regex` and pointers to it.
+pub trait IntoRegex<T: ?Sized = str>: Sized {
+    type Regex: SplitPattern<Text = T> + 'static;
+    type StableRegex: StableDeref<Target = Self::Regex>;
+
+    fn into_stable_regex(self) -> Self::StableRegex;
+}
+
+// An owned regex gets boxed, while a pointer to one is kept as it is.
+macro_rules! impl_into_regex {
+    ($($re:ty),*) => {$(
+        impl IntoRegex<<$re as SplitPattern>::Text> for $re {
+            type Regex = $re;
+            type StableRegex = Box<$re>;
+
+            fn into_stable_regex(self) -> Self::StableRegex {
+                self.into()
+            }
+        }
+
+        impl_into_regex!(@keep $re: &'_ $re, Box<$re>, std::rc::Rc<$re>, Arc<$re>);
+    )*};
+
+    (@keep $re:ty: $($t:ty),*) => {$(
+        impl IntoRegex<<$re as SplitPattern>::Text> for $t {
+            type Regex = $re;
+            type StableRegex = Self;
+
+            fn into_stable_regex(self) -> Self::StableRegex {
+                self
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "regex")]
+impl_into_regex!(regex::Regex, regex::bytes::Regex, regex_automata::meta::Regex);
+
+#[cfg(feature = "regex-lite")]
+impl_into_regex!(regex_lite::Regex);
+
+#[cfg(feature = "fancy-regex")]
+impl_into_regex!(fancy_regex::Regex);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_once() {
+        static CACHE: Mutex<Vec<Cached>> = Mutex::new(Vec::new());
+
+        let threads: Vec<_> = (0 .. 8)
+            .map(|_| std::thread::spawn(|| cached_in::<String>(&CACHE, r"\d+").unwrap()))
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!( CACHE.lock().unwrap().len(), 1 );
+
+        // The same pattern compiled as another type is cached separately.
+        let path = cached_in::<std::path::PathBuf>(&CACHE, r"\d+").unwrap();
+        assert_eq!( path.to_str(), Some(r"\d+") );
+        assert_eq!( CACHE.lock().unwrap().len(), 2 );
+    }
+
+    #[test]
+    fn cached_evicts() {
+        static CACHE: Mutex<Vec<Cached>> = Mutex::new(Vec::new());
+
+        let first = cached_in::<String>(&CACHE, " *; *").unwrap();
+        assert!( Arc::ptr_eq(&first, &cached_in(&CACHE, " *; *").unwrap()) );
+        for i in 0 .. CACHE_SIZE {
+            cached_in::<String>(&CACHE, &format!("p{}", i)).unwrap();
+        }
+        assert_eq!( CACHE.lock().unwrap().len(), CACHE_SIZE );
+        assert!( !Arc::ptr_eq(&first, &cached_in(&CACHE, " *; *").unwrap()) );
+        assert_eq!( Arc::strong_count(&first), 1 );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn meta_regex() {
+        use regex_automata::meta::Regex;
+
+        let number = Regex::new(r"\d+").unwrap();
+        assert_eq!( "12ab".split_first_regex(&number), Some(("12", "ab")) );
+        assert_eq!( "ab12".split_first_regex(&number), None );
+        assert_eq!( "ab12".split_last_regex(&number), Some(("12", "ab")) );
+        assert_eq!( "ab".split_first_regex(&Regex::new("a|ab").unwrap()), Some(("a", "b")) );
+        assert_eq!( "x".split_first_regex(&Regex::new(r"\d*").unwrap()), Some(("", "x")) );
+        assert_eq!( number.find_anchored_at("a1", 1), Ok(Some((1, 2))) );
+        assert_eq!( number.find_anchored_at("a1", 0), Ok(None) );
+
+        assert_eq!( Split::new(&number, "a1b22c").rev().collect::<Vec<_>>(), ["c", "b", "a"] );
+        let found: Vec<_> = Matches::within(&number, "a1b22c", 0, 6)
+            .map(|found| Regex::match_text(&found))
+            .collect();
+        assert_eq!( found, ["1", "22"] );
+
+        let ops = Regex::new(r"\s*([+*])\s*").unwrap();
+        assert_eq!( "1+2 * 3".split_regex_keep(&ops).collect::<Vec<_>>(),
+                    ["1", "+", "2", "*", "3"] );
+    }
+
+    #[cfg(feature = "regex-lite")]
+    #[test]
+    fn regex_lite() {
+        let digits = regex_lite::Regex::new(r"\d+").unwrap();
+        let split = Split::new(&digits, "a1b22c");
+        assert_eq!( split.collect::<Vec<_>>(), ["a", "b", "c"] );
+        let split = Split::within(&digits, "a1b22c", 2, 6);
+        assert_eq!( split.rev().collect::<Vec<_>>(), ["c", "b"] );
+
+        let empty = regex_lite::Regex::new("").unwrap();
+        assert_eq!( Split::new(&empty, "é€").collect::<Vec<_>>(), ["", "é", "€", ""] );
+
+        let found: Vec<_> = Matches::within(&digits, "a1b22c", 0, 6)
+            .map(|m| (m.start(), m.as_str()))
+            .collect();
+        assert_eq!( found, [(1, "1"), (3, "22")] );
+        assert_eq!( Matches::within(&digits, "a1b22c", 0, 6).next_back().map(|m| m.range()),
+                    Some(3 .. 5) );
+
+        let ops = regex_lite::Regex::new(r"\s*([+*])\s*").unwrap();
+        assert_eq!( "1+2 * 3".split_regex_keep(&ops).rev().collect::<Vec<_>>(),
+                    ["3", "*", "2", "+", "1"] );
+
+        assert_eq!( "12ab".split_first_regex(&digits), Some(("12", "ab")) );
+        assert_eq!( "ab12".split_last_regex(&digits), Some(("12", "ab")) );
+        assert_eq!( "ab12".split_first_regex(&digits), None );
+    }
+
+    #[cfg(all(feature = "regex-lite", feature = "into"))]
+    #[test]
+    fn regex_lite_into() {
+        use crate::IntoSplitIter;
+
+        let comma = regex_lite::Regex::new(" *, *").unwrap();
+        let fields = "a, b ,c".to_owned().into_split_regex(&comma);
+        assert_eq!( fields.rev().collect::<Vec<_>>(), ["c", "b", "a"] );
+
+        let fields = "a, b".into_split_pattern_with::<regex_lite::Regex>(" *, *").unwrap();
+        assert_eq!( fields.collect::<Vec<_>>(), ["a", "b"] );
+
+        let digits = regex_lite::Regex::new(r"\d+").unwrap();
+        let mut found = "a1b22c".to_owned().into_find_iter(&digits);
+        assert_eq!( found.next(), Some((1 .. 2, "1".to_owned())) );
+        assert_eq!( found.remainder(), "b22c" );
+        assert_eq!( "a1b22c".into_match_spans(&digits).rev().collect::<Vec<_>>(),
+                    [3 .. 5, 1 .. 2] );
+
+        let keep = "a1b".into_split_regex_keep(digits.clone());
+        assert_eq!( keep.collect::<Vec<_>>(), ["a", "1", "b"] );
+    }
+
+    #[cfg(feature = "fancy-regex")]
+    #[test]
+    fn fancy_regex() {
+        let camel = fancy_regex::Regex::new("(?=[A-Z])").unwrap();
+        assert_eq!( Split::new(&camel, "helloWorldFoo").collect::<Vec<_>>(),
+                    ["hello", "World", "Foo"] );
+
+        let doubled = fancy_regex::Regex::new(r"(\w)\1").unwrap();
+        let mut split = Split::new(&doubled, "abccdeef");
+        assert_eq!( split.next_back(), Some("f") );
+        assert_eq!( split.collect::<Vec<_>>(), ["ab", "d"] );
+
+        let empty = fancy_regex::Regex::new("").unwrap();
+        assert_eq!( Split::new(&empty, "é€").collect::<Vec<_>>(), ["", "é", "€", ""] );
+    }
+
+    #[cfg(feature = "fancy-regex")]
+    #[test]
+    fn fancy_regex_error() {
+        let slow = fancy_regex::Regex::new(r"(a+)+\1b").unwrap();
+        let text = format!("x{}", "a".repeat(40));
+        let mut split = Split::new(&slow, &text);
+        assert!( split.take_error().is_none() );
+        assert_eq!( split.next(), None );
+        assert_eq!( split.next_back(), None );
+        assert_eq!( split.remainder(), text );
+        assert!( matches!(split.take_error(),
+                          Some(fancy_regex::Error::RuntimeError(
+                              fancy_regex::RuntimeError::BacktrackLimitExceeded))) );
+        assert_eq!( split.next(), None );
+
+        let mut found = Matches::within(&slow, &text, 0, text.len());
+        assert!( found.next().is_none() );
+        assert!( found.take_error().is_some() );
+
+        let mut keep = text.split_regex_keep(&slow);
+        assert_eq!( keep.next(), None );
+        assert!( keep.take_error().is_some() );
+
+        assert!( text.as_str().try_split_last_regex(&slow).is_err() );
+    }
+
+    #[cfg(feature = "fancy-regex")]
+    #[test]
+    fn fancy_regex_keep_and_end() {
+        let doubled = fancy_regex::Regex::new(r"(\w)\1").unwrap();
+        assert_eq!( "abccdeef".split_regex_keep(&doubled).collect::<Vec<_>>(),
+                    ["ab", "c", "d", "e", "f"] );
+        let found: Vec<_> = Matches::within(&doubled, "abccdeef", 0, 8)
+            .map(|m| m.as_str())
+            .collect();
+        assert_eq!( found, ["cc", "ee"] );
+
+        assert_eq!( "aab".try_split_first_regex(&doubled).unwrap(), Some(("aa", "b")) );
+        assert_eq!( "abb".try_split_last_regex(&doubled).unwrap(), Some(("bb", "a")) );
+        assert_eq!( "ab".try_split_last_regex(&doubled).unwrap(), None );
+    }
+
+    #[cfg(all(feature = "fancy-regex", feature = "into"))]
+    #[test]
+    fn fancy_regex_into() {
+        use crate::IntoSplitIter;
+
+        let after_comma = fancy_regex::Regex::new("(?<=,)").unwrap();
+        let mut fields = "a,b,c".to_owned().into_split_regex(after_comma);
+        assert_eq!( fields.next(), Some("a,".to_owned()) );
+        assert_eq!( fields.remainder(), "b,c" );
+        assert_eq!( fields.next_str(), Some("b,") );
+        assert_eq!( fields.next_back(), Some("c".to_owned()) );
+        assert_eq!( fields.next(), None );
+        assert!( fields.take_error().is_none() );
+
+        let slow = fancy_regex::Regex::new(r"(a+)+\1b").unwrap();
+        let mut pieces = "a".repeat(40).into_split_regex_map(&slow, str::len);
+        assert_eq!( pieces.next(), None );
+        assert_eq!( pieces.remainder().len(), 40 );
+        assert!( pieces.take_error().is_some() );
+
+        let mut found = "a".repeat(40).into_find_iter(&slow);
+        assert_eq!( found.next(), None );
+        assert!( found.take_error().is_some() );
+
+        let ahead = "aXbXc".into_split_pattern_with::<fancy_regex::Regex>(r"X(?=\w)");
+        assert_eq!( ahead.unwrap().collect::<Vec<_>>(), ["a", "b", "c"] );
+        assert!( "x".into_split_pattern_with::<fancy_regex::Regex>("(").is_err() );
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(unused)]
 
-use std::{iter, str};
+use core::{iter, str};
 
 pub fn char_boundaries<'a>(s: &'a str)
     -> impl DoubleEndedIterator<Item = usize> + 'a {